@@ -3,15 +3,20 @@
 // This wrapper enables querying OpenWeather One Call API 3.0 endpoints
 // as PostgreSQL foreign tables using WASM FDW.
 //
-// Supported endpoints (v0.2.0 - 8 tables from 4 API endpoints):
+// Supported endpoints (18 tables; 16 served by 9 API endpoints, 2 local):
 // - current_weather: Current weather conditions (1 row)
 //   API: /onecall → parses 'current' section
-//   Parameters: lat, lon, units (optional), lang (optional)
+//        (or /data/2.5/weather when queried by city_id)
+//   Parameters: lat, lon (or city_id), units (optional), lang (optional)
 //
 // - minutely_forecast: Minute-by-minute precipitation forecast (60 rows)
 //   API: /onecall → parses 'minutely' array
 //   Parameters: lat, lon, units (optional), lang (optional)
 //
+// - minutely_summary: Next-hour precipitation rollup (1 row)
+//   API: /onecall → aggregates 'minutely' array
+//   Parameters: lat, lon, units (optional), lang (optional)
+//
 // - hourly_forecast: Hourly forecast for 48 hours (48 rows)
 //   API: /onecall → parses 'hourly' array
 //   Parameters: lat, lon, units (optional), lang (optional)
@@ -20,69 +25,195 @@
 //   API: /onecall → parses 'daily' array
 //   Parameters: lat, lon, units (optional), lang (optional)
 //
+// - rain_outlook: Will it rain within the next N hours (1 row)
+//   API: /onecall → aggregates 'hourly' array
+//   Parameters: lat, lon, units (optional), lang (optional)
+//
+// - forecast: Hourly and daily forecasts in one shape (56 rows)
+//   API: /onecall → 'hourly' and 'daily' arrays, tagged by granularity
+//   Parameters: lat, lon, units (optional), lang (optional)
+//
 // - weather_alerts: Government weather alerts (0-N rows)
 //   API: /onecall → parses 'alerts' array
 //   Parameters: lat, lon, units (optional), lang (optional)
 //
+// - fetch_all_onecall: Dashboard snapshot across One Call sections (1 row)
+//   API: /onecall → combines 'current', 'minutely', 'daily[0]' and 'alerts'
+//   Parameters: lat, lon, units (optional), lang (optional)
+//
 // - historical_weather: Historical weather data (1 row)
 //   API: /onecall/timemachine → parses 'data[0]'
 //   Parameters: lat, lon, dt (unix timestamp), units (optional), lang (optional)
 //
-// - daily_summary: Daily aggregated weather statistics (1 row)
-//   API: /onecall/day_summary → parses nested aggregations
-//   Parameters: lat, lon, date (YYYY-MM-DD), tz (optional), units (optional), lang (optional)
+// - daily_summary: Daily aggregated weather statistics (1 row per date)
+//   API: /onecall/day_summary → parses nested aggregations (one call per date)
+//   Parameters: lat, lon, date (YYYY-MM-DD or range), tz (optional), units (optional), lang (optional)
 //
 // - weather_overview: AI-generated weather summary (1 row)
 //   API: /onecall/overview → parses AI summary text
 //   Parameters: lat, lon, date (optional), units (optional), lang (optional)
 //
+// - air_pollution: Current air quality index and pollutant concentrations (1 row)
+//   API: /data/2.5/air_pollution → parses 'list[0]'
+//   Parameters: lat, lon
+//
+// - cities_in_bbox: Current weather for every city in a bounding box (0-N rows)
+//   API: /data/2.5/box/city → parses 'list' array
+//   Parameters: min_lat, min_lon, max_lat, max_lon, zoom (optional), units (optional), lang (optional)
+//
+// - cities_group: Current weather for a list of OpenWeather city IDs (1-20 rows)
+//   API: /data/2.5/group → parses 'list' array
+//   Parameters: city_ids (comma-separated) or city_id IN (...), units (optional), lang (optional)
+//
+// - geocoding: Coordinates for a location name (0-5 rows)
+//   API: /geo/1.0/direct → parses array of matches
+//   Parameters: q (city_name), limit (optional, 1-5)
+//
+// - request_metadata: Details of the last API request (0-1 rows)
+//   API: none → reads metadata persisted by the previous fetch
+//   Parameters: none
+//
+// - api_usage_log: Writable API usage audit counters (0-N rows)
+//   API: none → INSERT records usage, SELECT reads the persisted counters
+//   Parameters: none
+//
 // API Documentation: https://openweathermap.org/api/one-call-3
 // Implementation Plan: docs/IMPLEMENTATION_PLAN.md
 
 #[allow(warnings)]
 mod bindings;
 
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
 use serde_json::Value as JsonValue;
 
 use bindings::{
     exports::supabase::wrappers::routines::Guest,
     supabase::wrappers::{
-        http, stats,
+        http, stats, time,
         types::{
-            Cell, Column, Context, FdwError, FdwResult, ImportForeignSchemaStmt, OptionsType, Row,
-            Value,
+            Cell, Context, FdwError, FdwResult, ImportForeignSchemaStmt, ImportSchemaType, Options,
+            OptionsType, Row, TypeOid, Value,
         },
         utils,
     },
 };
 
-/// Supported OpenWeather One Call API 3.0 endpoints
-/// v0.2.0: 8 foreign tables mapping to 4 API endpoints
+/// Supported OpenWeather endpoints, one per foreign table
+/// (One Call API 3.0 plus the 2.5 air pollution, city and geocoding APIs)
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum EndpointType {
     CurrentWeather,    // /onecall → current
     MinutelyForecast,  // /onecall → minutely[]
+    MinutelySummary,   // /onecall → minutely[] aggregated
     HourlyForecast,    // /onecall → hourly[]
     DailyForecast,     // /onecall → daily[]
+    RainOutlook,       // /onecall → hourly[] aggregated
+    Forecast,          // /onecall → hourly[] + daily[] unified
     WeatherAlerts,     // /onecall → alerts[]
+    OneCallSummary,    // /onecall → current + minutely + daily[0] + alerts
     HistoricalWeather, // /onecall/timemachine → data[0]
     DailySummary,      // /onecall/day_summary → daily aggregations
     WeatherOverview,   // /onecall/overview → AI weather summary
+    AirPollution,      // /data/2.5/air_pollution → list[0]
+    CitiesInBbox,      // /data/2.5/box/city → list[]
+    CitiesGroup,       // /data/2.5/group → list[]
+    Geocoding,         // /geo/1.0/direct → location matches
+    RequestMetadata,   // no API call → last request details
+    ApiUsageLog,       // no API call → writable usage audit counters
 }
 
 impl EndpointType {
+    /// All endpoints, in the order they are listed in error messages
+    const ALL: [EndpointType; 18] = [
+        EndpointType::CurrentWeather,
+        EndpointType::MinutelyForecast,
+        EndpointType::MinutelySummary,
+        EndpointType::HourlyForecast,
+        EndpointType::DailyForecast,
+        EndpointType::RainOutlook,
+        EndpointType::Forecast,
+        EndpointType::WeatherAlerts,
+        EndpointType::OneCallSummary,
+        EndpointType::HistoricalWeather,
+        EndpointType::DailySummary,
+        EndpointType::WeatherOverview,
+        EndpointType::AirPollution,
+        EndpointType::CitiesInBbox,
+        EndpointType::CitiesGroup,
+        EndpointType::Geocoding,
+        EndpointType::RequestMetadata,
+        EndpointType::ApiUsageLog,
+    ];
+
     /// Parse endpoint type from OPTIONS object parameter
     fn from_object_name(name: &str) -> Result<Self, FdwError> {
         match name {
             "current_weather" => Ok(EndpointType::CurrentWeather),
             "minutely_forecast" => Ok(EndpointType::MinutelyForecast),
+            "minutely_summary" => Ok(EndpointType::MinutelySummary),
             "hourly_forecast" => Ok(EndpointType::HourlyForecast),
             "daily_forecast" => Ok(EndpointType::DailyForecast),
+            "rain_outlook" => Ok(EndpointType::RainOutlook),
+            "forecast" => Ok(EndpointType::Forecast),
             "weather_alerts" => Ok(EndpointType::WeatherAlerts),
+            "fetch_all_onecall" => Ok(EndpointType::OneCallSummary),
             "historical_weather" => Ok(EndpointType::HistoricalWeather),
             "daily_summary" => Ok(EndpointType::DailySummary),
             "weather_overview" => Ok(EndpointType::WeatherOverview),
-            _ => Err(format!("unsupported endpoint object '{}'. Supported: current_weather, minutely_forecast, hourly_forecast, daily_forecast, weather_alerts, historical_weather, daily_summary, weather_overview", name)),
+            "air_pollution" => Ok(EndpointType::AirPollution),
+            "cities_in_bbox" => Ok(EndpointType::CitiesInBbox),
+            "cities_group" => Ok(EndpointType::CitiesGroup),
+            "geocoding" => Ok(EndpointType::Geocoding),
+            "request_metadata" => Ok(EndpointType::RequestMetadata),
+            "api_usage_log" => Ok(EndpointType::ApiUsageLog),
+            _ => {
+                let suggestion = Self::closest_object_name(name)
+                    .map(|closest| format!(" Did you mean '{}'?", closest))
+                    .unwrap_or_default();
+                let supported: Vec<&str> = Self::ALL.iter().map(|e| e.object_name()).collect();
+                Err(format!(
+                    "unsupported endpoint object '{}'.{} Supported: {}",
+                    name,
+                    suggestion,
+                    supported.join(", ")
+                ))
+            }
+        }
+    }
+
+    /// Suggest the known object name closest to a misspelled one (at most 3 edits away)
+    fn closest_object_name(name: &str) -> Option<&'static str> {
+        Self::ALL
+            .iter()
+            .map(|e| (edit_distance(name, e.object_name()), e.object_name()))
+            .filter(|(distance, _)| *distance <= 3)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, object_name)| object_name)
+    }
+
+    /// Get the OPTIONS object name for this endpoint
+    fn object_name(&self) -> &'static str {
+        match self {
+            EndpointType::CurrentWeather => "current_weather",
+            EndpointType::MinutelyForecast => "minutely_forecast",
+            EndpointType::MinutelySummary => "minutely_summary",
+            EndpointType::HourlyForecast => "hourly_forecast",
+            EndpointType::DailyForecast => "daily_forecast",
+            EndpointType::RainOutlook => "rain_outlook",
+            EndpointType::Forecast => "forecast",
+            EndpointType::WeatherAlerts => "weather_alerts",
+            EndpointType::OneCallSummary => "fetch_all_onecall",
+            EndpointType::HistoricalWeather => "historical_weather",
+            EndpointType::DailySummary => "daily_summary",
+            EndpointType::WeatherOverview => "weather_overview",
+            EndpointType::AirPollution => "air_pollution",
+            EndpointType::CitiesInBbox => "cities_in_bbox",
+            EndpointType::CitiesGroup => "cities_group",
+            EndpointType::Geocoding => "geocoding",
+            EndpointType::RequestMetadata => "request_metadata",
+            EndpointType::ApiUsageLog => "api_usage_log",
         }
     }
 
@@ -91,27 +222,154 @@ impl EndpointType {
         match self {
             EndpointType::CurrentWeather
             | EndpointType::MinutelyForecast
+            | EndpointType::MinutelySummary
             | EndpointType::HourlyForecast
             | EndpointType::DailyForecast
-            | EndpointType::WeatherAlerts => "/onecall",
+            | EndpointType::RainOutlook
+            | EndpointType::Forecast
+            | EndpointType::WeatherAlerts
+            | EndpointType::OneCallSummary => "/onecall",
             EndpointType::HistoricalWeather => "/onecall/timemachine",
             EndpointType::DailySummary => "/onecall/day_summary",
             EndpointType::WeatherOverview => "/onecall/overview",
+            EndpointType::AirPollution => "/data/2.5/air_pollution",
+            EndpointType::CitiesInBbox => "/data/2.5/box/city",
+            EndpointType::CitiesGroup => "/data/2.5/group",
+            EndpointType::Geocoding => "/geo/1.0/direct",
+            EndpointType::RequestMetadata | EndpointType::ApiUsageLog => "",
         }
     }
 
+    /// Get the HTTP method used to call this endpoint
+    ///
+    /// Every OpenWeather endpoint used so far is GET; POST-based endpoints would be
+    /// listed here and pass a body to build_request.
+    fn http_method(&self) -> http::Method {
+        http::Method::Get
+    }
+
     /// Check if endpoint calls /onecall (shared response parsing)
-    #[allow(dead_code)]
     fn calls_onecall(&self) -> bool {
         matches!(
             self,
             EndpointType::CurrentWeather
                 | EndpointType::MinutelyForecast
+                | EndpointType::MinutelySummary
                 | EndpointType::HourlyForecast
                 | EndpointType::DailyForecast
+                | EndpointType::RainOutlook
+                | EndpointType::Forecast
                 | EndpointType::WeatherAlerts
+                | EndpointType::OneCallSummary
+        )
+    }
+
+    /// /onecall sections this endpoint cannot be parsed without (the include option
+    /// must list them). Columns derived from other sections are NULL when those are
+    /// left out, e.g. current_weather's temperature_trend without 'hourly'.
+    fn required_onecall_sections(&self) -> &'static [&'static str] {
+        match self {
+            EndpointType::CurrentWeather | EndpointType::OneCallSummary => &["current"],
+            EndpointType::MinutelyForecast | EndpointType::MinutelySummary => &["minutely"],
+            EndpointType::HourlyForecast | EndpointType::RainOutlook => &["hourly"],
+            EndpointType::DailyForecast => &["daily"],
+            EndpointType::Forecast => &["hourly", "daily"],
+            EndpointType::WeatherAlerts => &["alerts"],
+            _ => &[],
+        }
+    }
+
+    /// Check if endpoint requires latitude/longitude in the WHERE clause
+    fn requires_location(&self) -> bool {
+        !matches!(
+            self,
+            EndpointType::CitiesInBbox
+                | EndpointType::CitiesGroup
+                | EndpointType::Geocoding
+                | EndpointType::RequestMetadata
+                | EndpointType::ApiUsageLog
         )
     }
+
+    /// Recommended seconds between refreshes, from how often OpenWeather updates the
+    /// data (None when it never changes, e.g. past weather and geocoding)
+    fn recommended_refresh_seconds(&self) -> Option<i64> {
+        match self {
+            EndpointType::CurrentWeather
+            | EndpointType::MinutelyForecast
+            | EndpointType::MinutelySummary
+            | EndpointType::WeatherAlerts
+            | EndpointType::OneCallSummary
+            | EndpointType::AirPollution
+            | EndpointType::CitiesInBbox
+            | EndpointType::CitiesGroup => Some(600),
+            EndpointType::HourlyForecast
+            | EndpointType::RainOutlook
+            | EndpointType::Forecast
+            | EndpointType::WeatherOverview => Some(3_600),
+            EndpointType::DailyForecast | EndpointType::DailySummary => Some(86_400),
+            EndpointType::HistoricalWeather
+            | EndpointType::Geocoding
+            | EndpointType::RequestMetadata
+            | EndpointType::ApiUsageLog => None,
+        }
+    }
+
+    /// Time column a forecast endpoint emits rows in (ascending by default)
+    fn sort_column(&self) -> Option<&'static str> {
+        match self {
+            EndpointType::MinutelyForecast | EndpointType::HourlyForecast => Some("forecast_time"),
+            EndpointType::DailyForecast => Some("forecast_date"),
+            _ => None,
+        }
+    }
+
+    /// Top-level response keys at least one of which every valid response carries
+    /// (empty when the response is not an object, e.g. geocoding's array)
+    fn expected_response_keys(&self, legacy_weather: bool) -> &'static [&'static str] {
+        match self {
+            EndpointType::CurrentWeather if legacy_weather => &["coord", "main", "weather", "dt"],
+            EndpointType::CurrentWeather
+            | EndpointType::MinutelyForecast
+            | EndpointType::MinutelySummary
+            | EndpointType::HourlyForecast
+            | EndpointType::DailyForecast
+            | EndpointType::RainOutlook
+            | EndpointType::Forecast
+            | EndpointType::WeatherAlerts
+            | EndpointType::OneCallSummary => &["lat", "lon", "timezone", "timezone_offset"],
+            EndpointType::HistoricalWeather => &["lat", "lon", "timezone", "data"],
+            EndpointType::DailySummary => &["lat", "lon", "date", "temperature"],
+            EndpointType::WeatherOverview => &["lat", "lon", "date", "weather_overview"],
+            EndpointType::AirPollution => &["coord", "list"],
+            EndpointType::CitiesInBbox | EndpointType::CitiesGroup => &["cnt", "list"],
+            EndpointType::Geocoding | EndpointType::RequestMetadata | EndpointType::ApiUsageLog => {
+                &[]
+            }
+        }
+    }
+
+    /// Request-parameter quals that only one endpoint sends to the API
+    const PARAMETER_QUALS: [(&'static str, EndpointType); 5] = [
+        ("observation_time", EndpointType::HistoricalWeather),
+        ("dt", EndpointType::HistoricalWeather),
+        ("summary_date", EndpointType::DailySummary),
+        ("timezone_offset", EndpointType::DailySummary),
+        ("overview_date", EndpointType::WeatherOverview),
+    ];
+
+    /// Parameter quals among `fields` that this endpoint does not send to the API
+    fn ignored_parameter_quals<'a>(&self, fields: &'a [String]) -> Vec<&'a str> {
+        fields
+            .iter()
+            .map(String::as_str)
+            .filter(|field| {
+                EndpointType::PARAMETER_QUALS
+                    .iter()
+                    .any(|(name, owner)| name == field && owner != self)
+            })
+            .collect()
+    }
 }
 
 /// Endpoint-specific data storage
@@ -124,24 +382,32 @@ enum EndpointData {
 
     // /onecall → current_weather (1 row)
     CurrentWeather {
+        city_id: Option<i64>, // Set when queried by city_id (legacy /weather)
         latitude: f64,
         longitude: f64,
-        timezone_name: String,
-        observation_time: i64, // Unix seconds (convert to TIMESTAMPTZ in output)
+        timezone_name: Option<String>, // Not provided by the legacy /weather endpoint
+        timezone_offset_seconds: i64,  // Shift from UTC in seconds
+        observation_time: i64,         // Unix seconds (convert to TIMESTAMPTZ in output)
+        sunrise_time: Option<i64>,     // Unix seconds (absent during polar day/night)
+        sunset_time: Option<i64>,      // Unix seconds (absent during polar day/night)
         temperature_temp: f64,
         apparent_temperature_temp: f64,
-        pressure_hpa: i64,
+        pressure_hpa: Option<i64>,
         humidity_pct: i64,
-        dew_point_temp: f64,
-        uv_index: f64,
+        dew_point_temp: Option<f64>, // Not provided by the legacy /weather endpoint
+        uv_index: Option<f64>,       // Not provided by the legacy /weather endpoint
         cloud_cover_pct: i64,
-        visibility_m: i64,
+        visibility_m: Option<i64>, // Omitted by the API for some observations
         wind_speed_m_s: f64,
         wind_direction_deg: i64,
         wind_gust_speed_m_s: Option<f64>,
         weather_condition: String,
+        weather_condition_code: Option<i64>, // weather[0].id (e.g. 500 = light rain)
         weather_description: String,
         weather_icon_code: String,
+        weather_all: String,              // Full 'weather' array as JSON
+        upcoming_temps: Vec<f64>,         // hourly[0..3] temperatures for temperature_trend
+        active_alerts_count: Option<i64>, // Not provided by the legacy /weather endpoint
     },
 
     // /onecall → minutely (60 rows)
@@ -152,39 +418,71 @@ enum EndpointData {
         precipitation_mm: Vec<f64>,
     },
 
+    // /onecall → minutely aggregated (1 row)
+    MinutelySummary {
+        latitude: f64,
+        longitude: f64,
+        total_precip_mm: f64,
+        minutes_until_precip: Option<i64>, // First minute index with precipitation > 0
+        peak_precip_mm: f64,
+    },
+
+    // /onecall → hourly aggregated over a horizon (1 row)
+    RainOutlook {
+        latitude: f64,
+        longitude: f64,
+        horizon_hours: i64,
+        pop_threshold: f64,
+        will_rain: bool,
+        rain_starts_at: Option<i64>, // Unix seconds of the first rainy hour
+        max_pop: f64,                // Highest precipitation probability within the horizon
+    },
+
+    // /onecall → hourly + daily in one shape (48 + 8 rows)
+    Forecast {
+        latitude: f64,
+        longitude: f64,
+        rows: Vec<ForecastRow>,
+    },
+
     // /onecall → hourly (48 rows)
     HourlyForecast {
         latitude: f64,
         longitude: f64,
-        forecast_time: Vec<i64>, // Unix seconds (convert to TIMESTAMPTZ in output)
+        timezone_offset_seconds: i64, // Shift from UTC in seconds
+        forecast_time: Vec<i64>,      // Unix seconds (convert to TIMESTAMPTZ in output)
         temperature_temp: Vec<f64>,
         apparent_temperature_temp: Vec<f64>,
-        pressure_hpa: Vec<i64>,
+        pressure_hpa: Vec<Option<i64>>,
         humidity_pct: Vec<i64>,
-        dew_point_temp: Vec<f64>,
-        uv_index: Vec<f64>,
+        dew_point_temp: Vec<Option<f64>>,
+        uv_index: Vec<Option<f64>>,
         cloud_cover_pct: Vec<i64>,
-        visibility_m: Vec<i64>,
+        visibility_m: Vec<Option<i64>>, // Omitted by the API for some hours
         wind_speed_m_s: Vec<f64>,
         wind_direction_deg: Vec<i64>,
         wind_gust_speed_m_s: Vec<Option<f64>>,
         precipitation_probability: Vec<f64>,
         rain_volume_1h_mm: Vec<Option<f64>>,
         snow_volume_1h_mm: Vec<Option<f64>>,
+        precipitation_type: Vec<&'static str>, // 'rain', 'snow', 'mixed' or 'none'
         weather_condition: Vec<String>,
+        weather_condition_code: Vec<Option<i64>>, // weather[0].id (e.g. 500 = light rain)
         weather_description: Vec<String>,
         weather_icon_code: Vec<String>,
+        weather_all: Vec<String>, // Full 'weather' array as JSON
     },
 
     // /onecall → daily (8 rows)
     DailyForecast {
         latitude: f64,
         longitude: f64,
-        forecast_date: Vec<i64>, // Unix seconds (convert to TIMESTAMPTZ in output)
-        sunrise_time: Vec<i64>,  // Unix seconds (convert to TIMESTAMPTZ in output)
-        sunset_time: Vec<i64>,   // Unix seconds (convert to TIMESTAMPTZ in output)
-        moonrise_time: Vec<i64>, // Unix seconds (convert to TIMESTAMPTZ in output)
-        moonset_time: Vec<i64>,  // Unix seconds (convert to TIMESTAMPTZ in output)
+        timezone_offset_seconds: i64, // Shift from UTC in seconds
+        forecast_date: Vec<i64>,      // Unix seconds (convert to TIMESTAMPTZ in output)
+        sunrise_time: Vec<i64>,       // Unix seconds (convert to TIMESTAMPTZ in output)
+        sunset_time: Vec<i64>,        // Unix seconds (convert to TIMESTAMPTZ in output)
+        moonrise_time: Vec<i64>,      // Unix seconds (convert to TIMESTAMPTZ in output)
+        moonset_time: Vec<i64>,       // Unix seconds (convert to TIMESTAMPTZ in output)
         moon_phase_fraction: Vec<f64>,
         temperature_day_temp: Vec<f64>,
         temperature_min_temp: Vec<f64>,
@@ -196,9 +494,9 @@ enum EndpointData {
         apparent_temperature_night_temp: Vec<f64>,
         apparent_temperature_evening_temp: Vec<f64>,
         apparent_temperature_morning_temp: Vec<f64>,
-        pressure_hpa: Vec<i64>,
+        pressure_hpa: Vec<Option<i64>>,
         humidity_pct: Vec<i64>,
-        dew_point_temp: Vec<f64>,
+        dew_point_temp: Vec<Option<f64>>,
         wind_speed_m_s: Vec<f64>,
         wind_direction_deg: Vec<i64>,
         wind_gust_speed_m_s: Vec<Option<f64>>,
@@ -206,10 +504,12 @@ enum EndpointData {
         precipitation_probability: Vec<f64>,
         rain_volume_mm: Vec<Option<f64>>,
         snow_volume_mm: Vec<Option<f64>>,
-        uv_index: Vec<f64>,
+        uv_index: Vec<Option<f64>>,
         weather_condition: Vec<String>,
+        weather_condition_code: Vec<Option<i64>>, // weather[0].id (e.g. 500 = light rain)
         weather_description: Vec<String>,
         weather_icon_code: Vec<String>,
+        day_summary_text: Vec<Option<String>>, // daily[].summary (One Call 3.0)
     },
 
     // /onecall → alerts (0-N rows)
@@ -219,44 +519,34 @@ enum EndpointData {
         alerts: Vec<AlertRow>,
     },
 
-    // /onecall/timemachine (1 row)
-    HistoricalWeather {
+    // /onecall → current + minutely + daily[0] + alerts (1 row)
+    OneCallSummary {
         latitude: f64,
         longitude: f64,
+        timezone_name: String,
         observation_time: i64, // Unix seconds (convert to TIMESTAMPTZ in output)
         temperature_temp: f64,
         apparent_temperature_temp: f64,
-        pressure_hpa: i64,
         humidity_pct: i64,
-        dew_point_temp: f64,
-        cloud_cover_pct: i64,
-        visibility_m: i64,
         wind_speed_m_s: f64,
-        wind_direction_deg: i64,
         weather_condition: String,
         weather_description: String,
-        weather_icon_code: String,
+        next_hour_precip_mm: Option<f64>, // Sum of minutely precipitation (None if unavailable)
+        today_min_temp: Option<f64>,
+        today_max_temp: Option<f64>,
+        active_alerts_count: Option<i64>, // None when include leaves out 'alerts'
     },
 
-    // /onecall/day_summary (1 row)
-    DailySummary {
+    // /onecall/timemachine (1 row)
+    HistoricalWeather {
         latitude: f64,
         longitude: f64,
-        timezone_offset: String,
-        summary_date: String,
-        unit_system: String,
-        temperature_min_temp: f64,
-        temperature_max_temp: f64,
-        temperature_morning_temp: f64,
-        temperature_afternoon_temp: f64,
-        temperature_evening_temp: f64,
-        temperature_night_temp: f64,
-        cloud_cover_afternoon_pct: f64,
-        humidity_afternoon_pct: f64,
-        pressure_afternoon_hpa: f64,
-        precipitation_total_mm: f64,
-        wind_max_speed_m_s: f64,
-        wind_max_direction_deg: f64,
+        observations: Vec<HistoricalObservation>,
+    },
+
+    // /onecall/day_summary (1 row per requested date)
+    DailySummary {
+        summaries: Vec<DailySummaryRow>,
     },
 
     // /onecall/overview (1 row)
@@ -267,18 +557,307 @@ enum EndpointData {
         overview_date: String,
         unit_system: String,
         weather_overview: String,
+        mentioned_high_temp: Option<f64>, // Parsed from the text (extract_overview_metrics)
+        mentioned_low_temp: Option<f64>,  // Parsed from the text (extract_overview_metrics)
+        api_message: Option<String>,      // Top-level 'message' noted on a successful response
+    },
+
+    // /data/2.5/air_pollution → list[0] (1 row)
+    AirPollution {
+        latitude: f64,
+        longitude: f64,
+        observation_time: i64, // Unix seconds (convert to TIMESTAMPTZ in output)
+        aqi: i64,              // Air Quality Index, 1 (Good) to 5 (Very Poor)
+        co_ug_m3: Option<f64>,
+        no_ug_m3: Option<f64>,
+        no2_ug_m3: Option<f64>,
+        o3_ug_m3: Option<f64>,
+        so2_ug_m3: Option<f64>,
+        pm2_5_ug_m3: Option<f64>,
+        pm10_ug_m3: Option<f64>,
+        nh3_ug_m3: Option<f64>,
+    },
+
+    // /data/2.5/box/city → list[] (0-N rows)
+    CitiesInBbox {
+        bbox: BoundingBox,
+        cities: Vec<CityWeatherRow>,
+    },
+
+    // /data/2.5/group → list[] (1-20 rows)
+    CitiesGroup {
+        city_ids: String, // Requested IDs, echoed as given in the city_ids qual
+        cities: Vec<CityWeatherRow>,
+    },
+
+    // /geo/1.0/direct (0-5 rows)
+    Geocoding {
+        city_name: String,
+        limit: i64,
+        matches: Vec<GeocodingRow>,
+    },
+
+    // Last request details (0-1 rows)
+    RequestMetadata {
+        metadata: Option<RequestMetadata>,
+    },
+
+    // Persisted usage audit counters (0-N rows)
+    ApiUsageLog {
+        entries: Vec<UsageLogEntry>,
+    },
+
+    // Requests a scan would send, built without calling the API (dry_run, 1 row per request)
+    DryRun {
+        requests: Vec<DryRunRequest>,
     },
 }
 
+/// Helper struct for one request a dry_run scan would have sent
+#[derive(Debug, Clone)]
+struct DryRunRequest {
+    request_url: String, // API key redacted
+    http_method: &'static str,
+    request_headers: String, // JSON object of header name → value
+    dt: Option<i64>,         // Timestamp requested (historical_weather)
+    date: Option<String>,    // Date requested (daily_summary, weather_overview)
+    // Remaining qual-bound parameters, echoed so PostgreSQL's recheck keeps the row
+    city_name: Option<String>,
+    city_id: Option<i64>,
+    city_ids: Option<String>, // As given in the city_ids qual (cities_group)
+    location: Option<String>,
+    units: String,
+    lang: String,
+    limit: Option<i64>, // Geocoding match limit
+    timezone_offset: Option<String>,
+    bbox: Option<BoundingBox>,
+}
+
 /// Helper struct for weather alerts
 #[derive(Debug, Clone)]
 struct AlertRow {
     alert_sender_name: String,
     alert_event_type: String,
-    alert_start_time: i64, // Unix seconds (convert to TIMESTAMPTZ in output)
-    alert_end_time: i64,   // Unix seconds (convert to TIMESTAMPTZ in output)
+    alert_event_normalized: String, // Lowercase, accent-free event for stable filtering
+    alert_start_time: i64,          // Unix seconds (convert to TIMESTAMPTZ in output)
+    alert_end_time: i64,            // Unix seconds (convert to TIMESTAMPTZ in output)
     alert_description: String,
+    alert_description_lang: Option<String>, // Declared language of the description, if any
     alert_tags: Vec<String>,
+    alert_severity: Option<String>, // Best-effort severity keyword found in tags
+}
+
+/// Details of the last API request, persisted via stats metadata so that
+/// the request_metadata table can read them from a later scan
+#[derive(Debug, Clone, Default)]
+struct RequestMetadata {
+    endpoint: String,
+    request_url: String, // API key redacted
+    status_code: i64,
+    response_bytes: i64,
+    request_count: i64, // HTTP calls issued so far (cumulative, cache hits excluded)
+    latency_ms: Option<i64>, // Wall-clock request time, whole seconds (absent in older metadata)
+}
+
+impl RequestMetadata {
+    fn to_json(&self) -> JsonValue {
+        serde_json::json!({
+            "endpoint": self.endpoint,
+            "request_url": self.request_url,
+            "status_code": self.status_code,
+            "response_bytes": self.response_bytes,
+            "request_count": self.request_count,
+            "latency_ms": self.latency_ms,
+        })
+    }
+
+    fn from_json(json: &JsonValue) -> Self {
+        let str_field = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        let int_field = |key: &str| json.get(key).and_then(|v| v.as_i64()).unwrap_or(0);
+        Self {
+            endpoint: str_field("endpoint"),
+            request_url: str_field("request_url"),
+            status_code: int_field("status_code"),
+            response_bytes: int_field("response_bytes"),
+            request_count: int_field("request_count"),
+            latency_ms: json.get("latency_ms").and_then(|v| v.as_i64()),
+        }
+    }
+}
+
+/// Usage counter for one (endpoint, latitude, longitude) combination, recorded by
+/// INSERT into api_usage_log and persisted via stats metadata
+#[derive(Debug, Clone)]
+struct UsageLogEntry {
+    endpoint: String,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    logged_count: i64,
+    last_logged_time: i64, // Unix seconds (convert to TIMESTAMPTZ in output)
+}
+
+impl UsageLogEntry {
+    fn to_json(&self) -> JsonValue {
+        serde_json::json!({
+            "endpoint": self.endpoint,
+            "latitude": self.latitude,
+            "longitude": self.longitude,
+            "logged_count": self.logged_count,
+            "last_logged_time": self.last_logged_time,
+        })
+    }
+
+    fn from_json(json: &JsonValue) -> Self {
+        let int_field = |key: &str| json.get(key).and_then(|v| v.as_i64()).unwrap_or(0);
+        Self {
+            endpoint: json
+                .get("endpoint")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+            latitude: json.get("latitude").and_then(|v| v.as_f64()),
+            longitude: json.get("longitude").and_then(|v| v.as_f64()),
+            logged_count: int_field("logged_count"),
+            last_logged_time: int_field("last_logged_time"),
+        }
+    }
+}
+
+/// Bounding box for cities_in_bbox, as given in the WHERE clause
+#[derive(Debug, Clone, Copy)]
+struct BoundingBox {
+    min_lat: f64,
+    min_lon: f64,
+    max_lat: f64,
+    max_lon: f64,
+    zoom: i64, // Map zoom level; lower zooms return fewer, larger cities
+}
+
+/// Helper struct for per-city current weather from the 2.5 list endpoints
+#[derive(Debug, Clone)]
+struct CityWeatherRow {
+    city_id: i64,
+    city_name: String,
+    latitude: f64,
+    longitude: f64,
+    observation_time: i64, // Unix seconds (convert to TIMESTAMPTZ in output)
+    temperature_temp: f64,
+    apparent_temperature_temp: Option<f64>,
+    pressure_hpa: Option<i64>,
+    humidity_pct: Option<i64>,
+    cloud_cover_pct: Option<i64>,
+    wind_speed_m_s: Option<f64>,
+    wind_direction_deg: Option<i64>,
+    weather_condition: String,
+    weather_condition_code: Option<i64>,
+    weather_description: String,
+    weather_icon_code: String,
+}
+
+/// Helper struct for one row of the unified forecast table; fields only one
+/// granularity reports are None for the other
+#[derive(Debug, Clone)]
+struct ForecastRow {
+    granularity: &'static str,         // "hourly" or "daily"
+    forecast_time: i64,                // Unix seconds (convert to TIMESTAMPTZ in output)
+    temperature_temp: f64,             // hourly temp, or daily temp.day
+    temperature_min_temp: Option<f64>, // daily only
+    temperature_max_temp: Option<f64>, // daily only
+    pressure_hpa: Option<i64>,
+    humidity_pct: Option<i64>,
+    wind_speed_m_s: Option<f64>,
+    precipitation_probability: Option<f64>,
+    rain_volume_mm: Option<f64>, // hourly rain.1h, or daily rain
+    snow_volume_mm: Option<f64>, // hourly snow.1h, or daily snow
+    visibility_m: Option<i64>,   // hourly only
+    weather_condition: String,
+    weather_condition_code: Option<i64>,
+    weather_description: String,
+    weather_icon_code: String,
+}
+
+/// Helper struct for one historical_weather observation (one per requested timestamp)
+#[derive(Debug, Clone)]
+struct HistoricalObservation {
+    observation_time: i64, // Unix seconds (convert to TIMESTAMPTZ in output)
+    temperature_temp: f64,
+    apparent_temperature_temp: f64,
+    pressure_hpa: Option<i64>,
+    humidity_pct: i64,
+    dew_point_temp: Option<f64>,
+    cloud_cover_pct: i64,
+    visibility_m: Option<i64>, // Omitted by the API for some observations
+    wind_speed_m_s: f64,
+    wind_direction_deg: i64,
+    weather_condition: String,
+    weather_condition_code: Option<i64>, // weather[0].id (e.g. 500 = light rain)
+    weather_description: String,
+    weather_icon_code: String,
+}
+
+/// Helper struct for geocoding matches
+#[derive(Debug, Clone)]
+struct GeocodingRow {
+    location_name: String,
+    latitude: f64,
+    longitude: f64,
+    country_code: String,
+    state_name: Option<String>,
+}
+
+/// Helper struct for daily summaries (one per requested date)
+#[derive(Debug, Clone)]
+struct DailySummaryRow {
+    latitude: f64,
+    longitude: f64,
+    timezone_offset: String,
+    summary_date: String,
+    unit_system: String,
+    temperature_min_temp: f64,
+    temperature_max_temp: f64,
+    temperature_morning_temp: f64,
+    temperature_afternoon_temp: f64,
+    temperature_evening_temp: f64,
+    temperature_night_temp: f64,
+    cloud_cover_afternoon_pct: f64,
+    humidity_afternoon_pct: f64,
+    pressure_afternoon_hpa: f64,
+    precipitation_total_mm: f64,
+    wind_max_speed_m_s: f64,
+    wind_max_direction_deg: f64,
+    api_message: Option<String>, // Top-level 'message' noted on a successful response
+}
+
+impl AlertRow {
+    /// Known severity keywords, ordered from most to least severe
+    const SEVERITY_LEVELS: [&'static str; 4] = ["Extreme", "Severe", "Moderate", "Minor"];
+
+    /// Scan alert tags for a known severity keyword, as a whole word (case-insensitive),
+    /// so "Minority" or "Nonsevere" do not count
+    fn severity_from_tags(tags: &[String]) -> Option<String> {
+        Self::SEVERITY_LEVELS
+            .iter()
+            .find(|level| {
+                tags.iter().any(|tag| {
+                    tag.split(|c: char| !c.is_alphanumeric())
+                        .any(|word| word.eq_ignore_ascii_case(level))
+                })
+            })
+            .map(|level| level.to_string())
+    }
+
+    /// Position in SEVERITY_LEVELS (0 = most severe), unknown severities last
+    fn severity_rank(&self) -> usize {
+        self.alert_severity
+            .as_deref()
+            .and_then(|severity| Self::SEVERITY_LEVELS.iter().position(|l| *l == severity))
+            .unwrap_or(Self::SEVERITY_LEVELS.len())
+    }
 }
 
 impl EndpointData {
@@ -288,12 +867,23 @@ impl EndpointData {
             EndpointData::None => 0,
             EndpointData::CurrentWeather { .. } => 1,
             EndpointData::MinutelyForecast { forecast_time, .. } => forecast_time.len(),
+            EndpointData::MinutelySummary { .. } => 1,
+            EndpointData::RainOutlook { .. } => 1,
+            EndpointData::Forecast { rows, .. } => rows.len(),
             EndpointData::HourlyForecast { forecast_time, .. } => forecast_time.len(),
             EndpointData::DailyForecast { forecast_date, .. } => forecast_date.len(),
             EndpointData::WeatherAlerts { alerts, .. } => alerts.len(),
-            EndpointData::HistoricalWeather { .. } => 1,
-            EndpointData::DailySummary { .. } => 1,
+            EndpointData::OneCallSummary { .. } => 1,
+            EndpointData::HistoricalWeather { observations, .. } => observations.len(),
+            EndpointData::DailySummary { summaries } => summaries.len(),
             EndpointData::WeatherOverview { .. } => 1,
+            EndpointData::AirPollution { .. } => 1,
+            EndpointData::CitiesInBbox { cities, .. } => cities.len(),
+            EndpointData::CitiesGroup { cities, .. } => cities.len(),
+            EndpointData::Geocoding { matches, .. } => matches.len(),
+            EndpointData::RequestMetadata { metadata } => metadata.iter().count(),
+            EndpointData::ApiUsageLog { entries } => entries.len(),
+            EndpointData::DryRun { requests } => requests.len(),
         }
     }
 
@@ -304,15 +894,42 @@ impl EndpointData {
     }
 }
 
+/// Cache key for a shared /onecall response: the request URL with the API key redacted
+/// (location, units, lang, exclude and extra_params all change the response) and a
+/// hash of the effective key, so a table with its own key never reuses another's call
+type OnecallCacheKey = (String, u64);
+
 /// FDW instance state
 #[derive(Debug, Default)]
 struct OpenWeatherFdw {
     /// API base URL
     base_url: String,
-    /// API key
+    /// API key used by the current scan (table override or the server key)
     api_key: String,
+    /// API key from the server options
+    server_api_key: String,
     /// HTTP headers for requests
     headers: Vec<(String, String)>,
+    /// Request timeout in milliseconds (server option, see fetch_json)
+    request_timeout_ms: Option<u64>,
+    /// One Call API version, "3.0" or "2.5" (server option, inferred from api_url)
+    api_version: String,
+    /// Serve current_weather from the lighter legacy /weather endpoint (server option)
+    fast_current: bool,
+    /// Return the requests a scan would send instead of calling the API (server option)
+    dry_run: bool,
+    /// Decimal places coordinates are rounded to before requests (server option)
+    coord_precision: Option<u32>,
+    /// Minimum spacing between outbound requests in milliseconds (server option)
+    min_request_interval_ms: Option<u64>,
+    /// Upper bound of a random delay added before each request (server option)
+    request_jitter_ms: Option<u64>,
+    /// Epoch seconds of the last outbound request from this instance
+    last_request_at: Option<i64>,
+    /// xorshift state for request jitter (seeded on first use)
+    jitter_state: u64,
+    /// Units applied when a query has no 'units' qual (server option, default "metric")
+    default_units: String,
     /// Current endpoint type
     endpoint_type: Option<EndpointType>,
     /// Endpoint-specific cached data
@@ -323,16 +940,311 @@ struct OpenWeatherFdw {
     units: String,                   // "metric", "imperial", or "standard"
     lang: String,                    // "en", "de", "es", etc.
     dt: Option<i64>,                 // Unix timestamp (historical_weather)
+    historical_dts: Vec<i64>,        // Unix timestamps to fetch (historical_weather)
     date: Option<String>,            // YYYY-MM-DD date (daily_summary, weather_overview)
-    timezone_offset: Option<String>, // Timezone offset +/-HHMM (daily_summary)
+    summary_dates: Vec<String>,      // YYYY-MM-DD dates to fetch (daily_summary)
+    timezone_offset: Option<String>, // Timezone offset as queried, ±HH:MM or ±HHMM (daily_summary)
+    city_name: Option<String>, // Location name as queried (geocoding, or weather tables; geocoded without lat/lon)
+    location: Option<String>,  // WKT point as queried, e.g. 'POINT(13.405 52.52)'
+    city_id: Option<i64>,      // OpenWeather city ID (current_weather via legacy /weather)
+    geocoding_limit: i64,      // Max matches, 1-5 (geocoding)
+    bbox: Option<BoundingBox>, // Area to list cities in (cities_in_bbox)
+    group_city_ids: Vec<i64>,  // OpenWeather city IDs, 1-20 (cities_group)
+    group_city_ids_text: String, // city_ids as given in the WHERE clause (cities_group)
+    geocode_city_name: bool,   // Weather table queried by city_name without lat/lon/location
+    alert_start_range: Option<(Bound<i64>, Bound<i64>)>, // alert_start_time quals, µs (weather_alerts)
+    alert_end_range: Option<(Bound<i64>, Bound<i64>)>, // alert_end_time quals, µs (weather_alerts)
+    /// Table options
+    error_on_empty: bool,         // Fail on empty day_summary/overview bodies (default true)
+    strict_row_count: bool, // Fail when forecast arrays have an unexpected length (default false)
+    strict_fields: bool, // Fail on missing pressure/dew_point/uvi instead of NULL (default false)
+    strict_quals: bool,  // Fail on parameter quals the endpoint ignores (default false)
+    check_response_shape: bool, // Fail when no expected top-level key is present (default true)
+    round_decimals: Option<u32>, // Round numeric weather outputs to this many decimals
+    description_fallback_lang: Option<String>, // Refetch empty weather descriptions in this lang
+    as_of: Option<i64>,  // Serve current_weather from timemachine at this Unix time
+    explode_weather: bool, // One hourly_forecast row per weather condition (default false)
+    extract_overview_metrics: bool, // Parse temperatures out of weather_overview (default false)
+    icon_size: String,   // weather_icon_url image size: "1x", "2x" (default) or "4x"
+    order_alerts: bool,  // Sort weather_alerts most severe first, then by start (default false)
+    location_tolerance_deg: Option<f64>, // Max response lat/lon drift from the request, degrees
+    extra_params: Option<String>, // Query string appended verbatim to every request URL
+    onecall_exclude: Option<String>, // /onecall 'exclude' value derived from the include option
+    rain_pop_threshold: f64, // Precipitation probability counted as rain (rain_outlook)
+    rain_horizon_hours: i64, // Hours of the hourly forecast considered (rain_outlook)
+    allow_empty: bool,   // Return no row for empty historical_weather data (default false)
+    /// Columns requested by the current scan (derived values are only built for these)
+    projected_columns: Vec<String>,
+    /// Details of the most recent API request
+    last_request: Option<RequestMetadata>,
+    /// Usage counters being updated by INSERT into api_usage_log (saved in end_modify)
+    usage_log: Vec<UsageLogEntry>,
+    /// Host clock (Unix seconds) when fetch_source_data ran, for the fetched_at column
+    fetched_at: Option<i64>,
     /// Current row index for iteration
     current_row: usize,
+    /// Emit rows last-to-first (ORDER BY forecast_time/forecast_date DESC)
+    descending: bool,
 }
 
+/// One Call API versions selectable with the api_version server option
+const SUPPORTED_API_VERSIONS: [&str; 2] = ["2.5", "3.0"];
+
+/// Legacy (API 2.5) current weather path, used for current_weather by city_id
+const LEGACY_WEATHER_PATH: &str = "/data/2.5/weather";
+
+/// User-Agent sent when the user_agent server option is not set
+const DEFAULT_USER_AGENT: &str = "Supabase Wrappers OpenWeather FDW";
+
+/// Unit systems accepted by the OpenWeather API
+///
+/// `units` changes temperatures (K / °C / °F) and wind speeds (m/s / m/s / mph) only;
+/// pressure (hPa), humidity (%), visibility (m) and precipitation (mm) are unit-independent.
+const SUPPORTED_UNITS: [&str; 3] = ["standard", "metric", "imperial"];
+
+/// Zoom level used for cities_in_bbox when the query gives none
+const DEFAULT_BBOX_ZOOM: i64 = 10;
+
+/// Column types accepted by the numeric_type import option
+const SUPPORTED_NUMERIC_TYPES: [&str; 3] = ["numeric", "double precision", "real"];
+
+/// Maximum number of city IDs accepted by one /group request
+const MAX_GROUP_CITY_IDS: usize = 20;
+
+/// Maximum number of matches returned by the geocoding API
+const MAX_GEOCODING_LIMIT: i64 = 5;
+
+/// Earliest timestamp served by the timemachine endpoint (1979-01-01T00:00:00Z)
+const MIN_HISTORICAL_DT: i64 = 283_996_800;
+
+/// Maximum number of timestamps a historical_weather IN list may hold (one call each)
+const MAX_HISTORICAL_TIMESTAMPS: usize = 24;
+
+/// Maximum number of days a daily_summary date range may span
+const MAX_SUMMARY_RANGE_DAYS: i64 = 366;
+
+/// Largest accepted value for the coord_precision server option
+const MAX_COORD_PRECISION: u32 = 6;
+
+/// Largest accepted value for the min_request_interval_ms and request_jitter_ms
+/// server options, so a misconfiguration cannot stall a query indefinitely
+const MAX_REQUEST_DELAY_MS: u64 = 60_000;
+
+/// rain_outlook defaults: precipitation probability above which an hour counts as
+/// rainy, and how many hours ahead to look
+const DEFAULT_RAIN_POP_THRESHOLD: f64 = 0.5;
+const DEFAULT_RAIN_HORIZON_HOURS: i64 = 12;
+
+/// Largest accepted value for the round_decimals table option
+const MAX_ROUND_DECIMALS: u32 = 10;
+
+/// Number of upcoming hourly temperatures smoothed for temperature_trend
+const TEMPERATURE_TREND_HOURS: usize = 3;
+
+/// Smoothing factor applied to each successive hourly temperature
+const TEMPERATURE_TREND_ALPHA: f64 = 0.5;
+
+/// Minimum smoothed change (in the requested units) reported as rising/falling
+const TEMPERATURE_TREND_THRESHOLD: f64 = 0.5;
+
+/// Request headers the FDW sets itself; dry_run redacts the values of any others
+const BUILTIN_HEADERS: [&str; 4] = ["user-agent", "accept", "accept-encoding", "content-type"];
+
+/// Base URL of OpenWeather's weather condition icons (weather_icon_url)
+const WEATHER_ICON_BASE_URL: &str = "https://openweathermap.org/img/wn";
+
+/// Length of an international mile in meters (visibility_mi)
+const METERS_PER_MILE: f64 = 1609.344;
+
+/// Knots per meter per second and per mile per hour (wind_speed_kt)
+const KNOTS_PER_M_S: f64 = 3600.0 / 1852.0;
+const KNOTS_PER_MPH: f64 = METERS_PER_MILE / 1852.0;
+
+/// Inches of mercury per hectopascal (pressure_inhg)
+const INHG_PER_HPA: f64 = 0.02953;
+
+/// Length of an inch in millimeters (rain/snow *_in columns)
+const MM_PER_INCH: f64 = 25.4;
+
+/// Top-level sections of a /onecall response, as named by its 'exclude' parameter
+const ONECALL_SECTIONS: [&str; 5] = ["current", "minutely", "hourly", "daily", "alerts"];
+
+/// Number of entries the One Call API documents for each forecast array
+const EXPECTED_MINUTELY_ROWS: usize = 60;
+const EXPECTED_HOURLY_ROWS: usize = 48;
+const EXPECTED_DAILY_ROWS: usize = 8;
+
 // Global state (required by WASM FDW interface)
 static mut INSTANCE: *mut OpenWeatherFdw = std::ptr::null_mut();
 static FDW_NAME: &str = "OpenWeatherFdw";
 
+/// Log an informational message through the host (a no-op in native unit tests,
+/// where host imports are unavailable)
+fn report_info(msg: &str) {
+    #[cfg(not(test))]
+    utils::report_info(msg);
+    #[cfg(test)]
+    let _ = msg;
+}
+
+/// Levenshtein distance between two strings (used for "did you mean" suggestions)
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+/// Characters after a keyword searched for its temperature (e.g. "highs around 24°C")
+const TEMPERATURE_MENTION_WINDOW: usize = 40;
+
+/// First temperature mentioned after one of `keywords` in free text, best effort.
+/// Only numbers followed by a degree marker ("°" or "degrees") count.
+fn mentioned_temperature(text: &str, keywords: &[&str]) -> Option<f64> {
+    let lower = text.to_lowercase();
+    keywords.iter().find_map(|keyword| {
+        lower.match_indices(keyword).find_map(|(pos, _)| {
+            // Skip keywords that end another word, e.g. "low" in "below"
+            if lower[..pos]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphabetic)
+            {
+                return None;
+            }
+            let window: String = lower[pos + keyword.len()..]
+                .chars()
+                .take(TEMPERATURE_MENTION_WINDOW)
+                .collect();
+            first_temperature(&window)
+        })
+    })
+}
+
+/// First number in `text` directly followed by a degree marker
+fn first_temperature(text: &str) -> Option<f64> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = if i > 0 && matches!(chars[i - 1], '-' | '−') {
+            i - 1
+        } else {
+            i
+        };
+        let mut end = i;
+        while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.') {
+            end += 1;
+        }
+        let number: String = chars[start..end]
+            .iter()
+            .map(|&c| if c == '−' { '-' } else { c })
+            .collect();
+        let rest: String = chars[end..].iter().collect();
+        let rest = rest.trim_start();
+        if rest.starts_with('°') || rest.starts_with("degree") {
+            if let Ok(value) = number.trim_end_matches('.').parse::<f64>() {
+                return Some(value);
+            }
+        }
+        i = end;
+    }
+    None
+}
+
+/// Parse a WKT point, 'POINT(lon lat)' with an optional 'SRID=n;' prefix, into (lon, lat)
+fn parse_wkt_point(text: &str) -> Option<(f64, f64)> {
+    let text = text.trim();
+    let text = match text.split_once(';') {
+        Some((srid, rest)) if srid.trim().to_ascii_uppercase().starts_with("SRID=") => rest,
+        _ => text,
+    };
+    let (kind, rest) = text.split_once('(')?;
+    if !kind.trim().eq_ignore_ascii_case("POINT") {
+        return None;
+    }
+    let mut coords = rest.trim_end().strip_suffix(')')?.split_whitespace();
+    let lon = coords.next()?.parse::<f64>().ok()?;
+    let lat = coords.next()?.parse::<f64>().ok()?;
+    match coords.next() {
+        None if lon.is_finite() && lat.is_finite() => Some((lon, lat)),
+        _ => None,
+    }
+}
+
+/// Lowercase text, strip Latin diacritics ("Überflutung" -> "uberflutung") and
+/// collapse whitespace; characters without an ASCII form are kept lowercased
+fn fold_to_ascii(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.to_lowercase().chars() {
+        let ascii = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+            'ç' | 'ć' | 'č' => "c",
+            'ď' | 'đ' => "d",
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+            'ğ' => "g",
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+            'ł' | 'ľ' | 'ĺ' => "l",
+            'ñ' | 'ń' | 'ň' => "n",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+            'ŕ' | 'ř' => "r",
+            'ś' | 'š' | 'ş' | 'ș' => "s",
+            'ť' | 'ţ' | 'ț' => "t",
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' | 'ų' => "u",
+            'ý' | 'ÿ' => "y",
+            'ź' | 'ż' | 'ž' => "z",
+            'ß' => "ss",
+            'æ' => "ae",
+            'œ' => "oe",
+            _ => {
+                folded.push(c);
+                continue;
+            }
+        };
+        folded.push_str(ascii);
+    }
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Describe a cell's type for error messages, e.g. "a string"
+fn cell_type_name(cell: &Cell) -> &'static str {
+    match cell {
+        Cell::Bool(_) => "a boolean",
+        Cell::I8(_) | Cell::I16(_) | Cell::I32(_) | Cell::I64(_) => "an integer",
+        Cell::F32(_) | Cell::F64(_) | Cell::Numeric(_) => "a number",
+        Cell::String(_) => "a string",
+        Cell::Date(_) => "a date",
+        Cell::Timestamp(_) | Cell::Timestamptz(_) => "a timestamp",
+        Cell::Json(_) => "a JSON value",
+        Cell::Uuid(_) => "a UUID",
+        Cell::Other(_) => "a value of another type",
+    }
+}
+
+/// Stats metadata key for api_usage_log counters (FDW_NAME holds request_metadata).
+/// FDW-owned keys use the "OpenWeatherFdw:<name>" form of the per-endpoint stats
+static USAGE_LOG_METADATA_KEY: &str = "OpenWeatherFdw:api_usage_log";
+
+/// Stats metadata key for the /onecall response shared across scans and tables
+static ONECALL_CACHE_METADATA_KEY: &str = "OpenWeatherFdw:onecall_cache";
+
+/// Seconds a cached /onecall response is reused (long enough to cover one query
+/// joining several /onecall-backed tables, short enough to stay current)
+const ONECALL_CACHE_TTL_SECS: i64 = 60;
+
+/// Maximum number of distinct api_usage_log entries kept (least recently logged dropped first)
+const MAX_USAGE_LOG_ENTRIES: usize = 1000;
+
 impl OpenWeatherFdw {
     fn init() {
         let instance = Self::default();
@@ -345,66 +1257,555 @@ impl OpenWeatherFdw {
         unsafe { &mut (*INSTANCE) }
     }
 
-    /// Extract numeric parameter from WHERE clause (for lat, lon, dt)
-    fn extract_qual_numeric(
-        quals: &[bindings::supabase::wrappers::types::Qual],
-        field: &str,
-    ) -> Option<f64> {
-        for qual in quals {
-            if qual.field() == field && qual.operator() == "=" {
-                return match qual.value() {
-                    Value::Cell(Cell::F64(n)) => Some(n),
-                    Value::Cell(Cell::I64(n)) => Some(n as f64),
-                    Value::Cell(Cell::I32(n)) => Some(n as f64),
-                    Value::Cell(Cell::Numeric(n)) => Some(n),
-                    _ => None,
-                };
+    /// Read an API key from server or table options: a Vault secret via 'api_key_id'
+    /// (recommended) or a plain text 'api_key' (deprecated)
+    fn api_key_option(opts: &Options) -> Result<Option<String>, FdwError> {
+        if let Some(vault_id) = opts.get("api_key_id") {
+            // Vault reference - secure method (RECOMMENDED)
+            utils::get_vault_secret(&vault_id).map(Some).ok_or_else(|| {
+                "Failed to retrieve API key from Vault. Ensure the secret exists and is accessible."
+                    .to_string()
+            })
+        } else if let Some(plain_key) = opts.get("api_key") {
+            // Plain text - deprecated but supported for backward compatibility
+            utils::report_warning(
+                "Using plain text 'api_key' is deprecated for security reasons. \
+                 Please migrate to 'api_key_id' with Vault. \
+                 See: https://supabase.com/docs/guides/database/vault",
+            );
+            Ok(Some(plain_key))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parse the extra_headers server option, a JSON object of header names to string
+    /// values (names are lowercased like the built-in headers)
+    fn parse_extra_headers(raw: &str) -> Result<Vec<(String, String)>, FdwError> {
+        let invalid = |detail: &str| {
+            format!(
+                "option 'extra_headers' must be a JSON object of header names to string values \
+                 (e.g. '{{\"x-proxy-token\": \"abc\"}}'): {}",
+                detail
+            )
+        };
+        let json: JsonValue = serde_json::from_str(raw).map_err(|e| invalid(&e.to_string()))?;
+        let object = json.as_object().ok_or_else(|| invalid("not an object"))?;
+
+        let mut headers = Vec::with_capacity(object.len());
+        for (name, value) in object {
+            let valid_name = !name.is_empty()
+                && name
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+            if !valid_name {
+                return Err(invalid(&format!("invalid header name '{}'", name)));
             }
+            let value = value
+                .as_str()
+                .filter(|v| !v.contains(['\r', '\n']))
+                .ok_or_else(|| invalid(&format!("invalid value for '{}'", name)))?;
+            headers.push((name.to_lowercase(), value.to_string()));
         }
-        None
+        Ok(headers)
     }
 
-    /// Extract string parameter from WHERE clause (for units, lang, date)
-    fn extract_qual_string(
-        quals: &[bindings::supabase::wrappers::types::Qual],
-        field: &str,
-    ) -> Option<String> {
-        quals
+    /// Turn the include table option ('current,daily') into the /onecall 'exclude'
+    /// value for the remaining sections (None when every section is included)
+    fn onecall_exclude(
+        endpoint_type: EndpointType,
+        include: &str,
+    ) -> Result<Option<String>, FdwError> {
+        if !endpoint_type.calls_onecall() {
+            return Err(format!(
+                "option 'include' only applies to tables served by /onecall, not {}",
+                endpoint_type.object_name()
+            ));
+        }
+        let mut included = Vec::new();
+        for section in include.split(',').map(|s| s.trim().to_lowercase()) {
+            if !ONECALL_SECTIONS.contains(&section.as_str()) {
+                return Err(format!(
+                    "option 'include' has unknown section '{}' (expected any of: {})",
+                    section,
+                    ONECALL_SECTIONS.join(", ")
+                ));
+            }
+            included.push(section);
+        }
+        if let Some(missing) = endpoint_type
+            .required_onecall_sections()
             .iter()
-            .find(|q| q.field() == field && q.operator() == "=")
+            .find(|section| !included.iter().any(|s| s == *section))
+        {
+            return Err(format!(
+                "option 'include' must list '{}' for {}",
+                missing,
+                endpoint_type.object_name()
+            ));
+        }
+        let excluded: Vec<&str> = ONECALL_SECTIONS
+            .iter()
+            .copied()
+            .filter(|section| !included.iter().any(|s| s == section))
+            .collect();
+        Ok((!excluded.is_empty()).then(|| excluded.join(",")))
+    }
+
+    /// Check the extra_params table option is a plain query string that leaves the
+    /// API key alone ('foo=bar&baz=1', already URL-encoded)
+    fn validate_extra_params(params: &str) -> FdwResult {
+        let invalid = |detail: String| {
+            format!(
+                "option 'extra_params' must be a URL-encoded query string like 'foo=bar&baz=1': {}",
+                detail
+            )
+        };
+        if params
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '?' | '#'))
+        {
+            return Err(invalid(format!(
+                "'{}' contains whitespace, '?' or '#'",
+                params
+            )));
+        }
+        for pair in params.split('&') {
+            let name = pair.split('=').next().unwrap_or_default();
+            if name.is_empty() {
+                return Err(invalid(format!("empty parameter name in '{}'", params)));
+            }
+            if name.eq_ignore_ascii_case("appid") {
+                return Err(invalid(
+                    "'appid' is not allowed; set the API key with api_key_id or api_key"
+                        .to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a boolean server/table option ('true' or 'false'), falling back to a default
+    fn bool_option(opts: &Options, key: &str, default: bool) -> Result<bool, FdwError> {
+        match opts.get(key).as_deref() {
+            None => Ok(default),
+            Some("true") => Ok(true),
+            Some("false") => Ok(false),
+            Some(other) => Err(format!(
+                "option '{}' must be 'true' or 'false', got '{}'",
+                key, other
+            )),
+        }
+    }
+
+    /// Parse an optional numeric server/table option
+    fn parse_option<T: std::str::FromStr>(
+        opts: &Options,
+        key: &str,
+    ) -> Result<Option<T>, FdwError> {
+        opts.get(key)
+            .map(|v| {
+                v.trim()
+                    .parse::<T>()
+                    .map_err(|_| format!("option '{}' must be a number, got '{}'", key, v))
+            })
+            .transpose()
+    }
+
+    /// Find the first qual on a field using one of the given operators, returning (operator, value)
+    fn find_qual(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+        field: &str,
+        operators: &[&str],
+    ) -> Option<(String, Cell)> {
+        quals
+            .iter()
+            .find(|q| q.field() == field && operators.contains(&q.operator().as_str()))
             .and_then(|q| match q.value() {
-                Value::Cell(Cell::String(s)) => Some(s),
+                Value::Cell(cell) => Some((q.operator(), cell)),
                 _ => None,
             })
     }
 
-    /// Extract TIMESTAMPTZ parameter from WHERE clause (returns microseconds)
+    /// Extract numeric parameter from WHERE clause (for lat, lon, dt)
+    fn extract_qual_numeric(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+        field: &str,
+    ) -> Option<f64> {
+        Self::find_qual(quals, field, &["="]).and_then(|(_, cell)| match cell {
+            Cell::F64(n) => Some(n),
+            Cell::F32(n) => Some(n as f64),
+            Cell::I64(n) => Some(n as f64),
+            Cell::I32(n) => Some(n as f64),
+            Cell::I16(n) => Some(n as f64),
+            Cell::I8(n) => Some(n as f64),
+            Cell::Numeric(n) => Some(n),
+            _ => None,
+        })
+    }
+
+    /// Extract integer parameter from WHERE clause (for zoom, limit, city_id, dt)
+    ///
+    /// Integer and integral numeric values are accepted; fractions, other types and
+    /// values outside `range` are errors. Returns None when the field has no `=` qual.
+    fn extract_qual_i64(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+        field: &str,
+        range: RangeInclusive<i64>,
+    ) -> Result<Option<i64>, FdwError> {
+        Self::find_qual(quals, field, &["="])
+            .map(|(_, cell)| Self::cell_to_i64(field, &cell, range))
+            .transpose()
+    }
+
+    /// Convert a qual value to an integer within `range` (see extract_qual_i64)
+    fn cell_to_i64(field: &str, cell: &Cell, range: RangeInclusive<i64>) -> Result<i64, FdwError> {
+        let integral = |n: f64| {
+            // i64::MAX as f64 rounds up to 2^63, so the upper bound is exclusive
+            if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+                Ok(n as i64)
+            } else {
+                Err(format!("{} must be an integer, got {}", field, n))
+            }
+        };
+        let value = match *cell {
+            Cell::I64(n) => n,
+            Cell::I32(n) => n as i64,
+            Cell::I16(n) => n as i64,
+            Cell::I8(n) => n as i64,
+            Cell::F64(n) | Cell::Numeric(n) => integral(n)?,
+            Cell::F32(n) => integral(n as f64)?,
+            _ => {
+                return Err(format!(
+                    "{} must be an integer, got {}",
+                    field,
+                    cell_type_name(cell)
+                ))
+            }
+        };
+        if !range.contains(&value) {
+            return Err(if *range.end() == i64::MAX {
+                format!(
+                    "{} must be an integer of at least {}, got {}",
+                    field,
+                    range.start(),
+                    value
+                )
+            } else {
+                format!(
+                    "{} must be an integer between {} and {}, got {}",
+                    field,
+                    range.start(),
+                    range.end(),
+                    value
+                )
+            });
+        }
+        Ok(value)
+    }
+
+    /// Extract string parameter from WHERE clause (for units, lang, date)
+    fn extract_qual_string(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+        field: &str,
+    ) -> Option<String> {
+        Self::find_qual(quals, field, &["="]).and_then(|(_, cell)| match cell {
+            Cell::String(s) => Some(s),
+            _ => None,
+        })
+    }
+
+    /// Extract a range from WHERE clause as (lower, upper) bounds
+    ///
+    /// `=` yields an inclusive single-value range, `>=`/`>` and `<=`/`<` yield
+    /// inclusive/exclusive bounds (BETWEEN arrives as `>=` plus `<=`).
+    /// Returns None when the field has no supported qual at all.
+    fn extract_qual_range(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+        field: &str,
+    ) -> Option<(Bound<Cell>, Bound<Cell>)> {
+        Self::qual_bounds(
+            Self::find_qual(quals, field, &["="]).map(|(_, cell)| cell),
+            Self::find_qual(quals, field, &[">=", ">"]),
+            Self::find_qual(quals, field, &["<=", "<"]),
+        )
+    }
+
+    /// Turn the `=` value and the (operator, value) lower and upper quals found on a
+    /// field into bounds; `=` wins over the others (see extract_qual_range)
+    fn qual_bounds(
+        eq: Option<Cell>,
+        lower: Option<(String, Cell)>,
+        upper: Option<(String, Cell)>,
+    ) -> Option<(Bound<Cell>, Bound<Cell>)> {
+        if let Some(cell) = eq {
+            return Some((Bound::Included(cell.clone()), Bound::Included(cell)));
+        }
+
+        let lower = match lower {
+            Some((op, cell)) if op == ">" => Bound::Excluded(cell),
+            Some((_, cell)) => Bound::Included(cell),
+            None => Bound::Unbounded,
+        };
+        let upper = match upper {
+            Some((op, cell)) if op == "<" => Bound::Excluded(cell),
+            Some((_, cell)) => Bound::Included(cell),
+            None => Bound::Unbounded,
+        };
+
+        match (&lower, &upper) {
+            (Bound::Unbounded, Bound::Unbounded) => None,
+            _ => Some((lower, upper)),
+        }
+    }
+
+    /// Extract a timestamptz range from WHERE clause as microsecond bounds
+    fn extract_qual_timestamptz_range(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+        field: &str,
+    ) -> Option<(Bound<i64>, Bound<i64>)> {
+        let to_micros = |bound: Bound<Cell>| match bound {
+            Bound::Included(Cell::Timestamptz(ts)) => Bound::Included(ts),
+            Bound::Excluded(Cell::Timestamptz(ts)) => Bound::Excluded(ts),
+            _ => Bound::Unbounded,
+        };
+        Self::extract_qual_range(quals, field)
+            .map(|(lower, upper)| (to_micros(lower), to_micros(upper)))
+    }
+
+    /// Parse a YYYY-MM-DD date into days since Unix epoch (None unless it is a real
+    /// calendar date in exactly that format)
+    fn parse_date(date: &str) -> Option<i64> {
+        let bytes = date.as_bytes();
+        let well_formed = bytes.len() == 10
+            && bytes.iter().enumerate().all(|(i, b)| match i {
+                4 | 7 => *b == b'-',
+                _ => b.is_ascii_digit(),
+            });
+        if !well_formed {
+            return None;
+        }
+        let year: i64 = date[0..4].parse().ok()?;
+        let month: i64 = date[5..7].parse().ok()?;
+        let day: i64 = date[8..10].parse().ok()?;
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        if !(1..=days_in_month).contains(&day) {
+            return None;
+        }
+
+        // Days-from-civil algorithm (proleptic Gregorian calendar)
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let mp = (month + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        Some(era * 146_097 + doe - 719_468)
+    }
+
+    /// Parse a timestamp option into Unix seconds: plain Unix seconds, or YYYY-MM-DD with
+    /// an optional HH:MM[:SS] time (space or 'T' separated) and 'Z' or ±HH[:MM] offset
+    /// (UTC when omitted)
+    fn parse_timestamp(text: &str) -> Option<i64> {
+        let text = text.trim();
+        if let Ok(secs) = text.parse::<i64>() {
+            return Some(secs);
+        }
+        let days = Self::parse_date(text.get(..10)?)?;
+        let rest = text.get(10..)?;
+        if rest.is_empty() {
+            return Some(days * 86_400);
+        }
+        let rest = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('T'))?;
+
+        // Split the time of day from an optional offset suffix
+        let (time, offset_secs) = match rest.find(['+', '-', 'Z']) {
+            Some(idx) if &rest[idx..] == "Z" => (&rest[..idx], 0),
+            Some(idx) => {
+                let offset = Self::normalize_timezone_offset(&rest[idx..]).ok()?;
+                let hours: i64 = offset[1..3].parse().ok()?;
+                let minutes: i64 = offset[4..6].parse().ok()?;
+                let sign = if offset.starts_with('-') { -1 } else { 1 };
+                (&rest[..idx], sign * (hours * 3_600 + minutes * 60))
+            }
+            None => (rest, 0),
+        };
+        let component = |part: Option<&str>, max: i64| {
+            part.filter(|p| p.len() == 2 && p.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|p| p.parse::<i64>().ok())
+                .filter(|value| *value <= max)
+        };
+        let mut parts = time.split(':');
+        let hour = component(parts.next(), 23)?;
+        let minute = component(parts.next(), 59)?;
+        let second = match parts.next() {
+            Some(part) => component(Some(part), 59)?,
+            None => 0,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(days * 86_400 + hour * 3_600 + minute * 60 + second - offset_secs)
+    }
+
+    /// Normalise a timezone offset to the ±HH:MM form the API expects, accepting
+    /// ±HH:MM, ±HHMM and ±HH
+    fn normalize_timezone_offset(tz: &str) -> Result<String, FdwError> {
+        let invalid = || format!("timezone_offset must be ±HH:MM (e.g. +02:00), got '{}'", tz);
+        let (sign, rest) = match tz.chars().next() {
+            Some(sign @ ('+' | '-')) if tz.is_ascii() => (sign, &tz[1..]),
+            _ => return Err(invalid()),
+        };
+        let digits = match rest.len() {
+            5 if &rest[2..3] == ":" => format!("{}{}", &rest[..2], &rest[3..]),
+            2 | 4 => rest.to_string(),
+            _ => return Err(invalid()),
+        };
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let hours: u32 = digits[..2].parse().map_err(|_| invalid())?;
+        let minutes: u32 = match &digits[2..] {
+            "" => 0,
+            minutes => minutes.parse().map_err(|_| invalid())?,
+        };
+        if hours > 14 || minutes > 59 {
+            return Err(invalid());
+        }
+        Ok(format!("{}{:02}:{:02}", sign, hours, minutes))
+    }
+
+    /// Check a date parameter before it reaches the API, returning days since epoch
+    fn validate_date(field: &str, date: &str) -> Result<i64, FdwError> {
+        Self::parse_date(date)
+            .ok_or_else(|| format!("{} must be YYYY-MM-DD, got '{}'", field, date))
+    }
+
+    /// Format days since Unix epoch as a YYYY-MM-DD date
+    fn format_date(days: i64) -> String {
+        // Civil-from-days algorithm (inverse of parse_date)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
+    /// Extract summary_date as a single date (=) or an inclusive range (>=/>, <=/<)
+    fn extract_summary_dates(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+    ) -> Result<Vec<String>, FdwError> {
+        let missing = || {
+            "WHERE clause must include 'summary_date' (YYYY-MM-DD format) for daily_summary. \
+             Example: WHERE latitude = 52.52 AND longitude = 13.405 AND summary_date = '2024-01-15' \
+             or summary_date BETWEEN '2024-01-01' AND '2024-01-31'"
+                .to_string()
+        };
+        let (lower, upper) = Self::extract_qual_range(quals, "summary_date").ok_or_else(missing)?;
+
+        // Single dates are sent verbatim once they are known to be real dates
+        if let (Bound::Included(Cell::String(lower)), Bound::Included(Cell::String(upper))) =
+            (&lower, &upper)
+        {
+            if lower == upper {
+                Self::validate_date("summary_date", lower)?;
+                return Ok(vec![lower.clone()]);
+            }
+        }
+
+        // Convert a bound to (date, days since epoch), moving exclusive bounds inward by one day
+        let to_days = |bound: Bound<Cell>, step: i64| -> Result<Option<(String, i64)>, FdwError> {
+            let (date, offset) = match bound {
+                Bound::Included(Cell::String(date)) => (date, 0),
+                Bound::Excluded(Cell::String(date)) => (date, step),
+                Bound::Unbounded => return Ok(None),
+                _ => {
+                    return Err(
+                        "summary_date must be compared against a YYYY-MM-DD string".to_string()
+                    )
+                }
+            };
+            let days = Self::validate_date("summary_date", &date)?;
+            Ok(Some((date, days + offset)))
+        };
+        let ((lower, start), (upper, end)) = match (to_days(lower, 1)?, to_days(upper, -1)?) {
+            (Some(lower), Some(upper)) => (lower, upper),
+            _ => return Err(missing()),
+        };
+
+        if start > end {
+            return Err(format!(
+                "summary_date range is empty: '{}' to '{}'",
+                lower, upper
+            ));
+        }
+        if end - start + 1 > MAX_SUMMARY_RANGE_DAYS {
+            return Err(format!(
+                "summary_date range spans {} days; maximum is {} (one API call per day)",
+                end - start + 1,
+                MAX_SUMMARY_RANGE_DAYS
+            ));
+        }
+
+        Ok((start..=end).map(Self::format_date).collect())
+    }
+
+    /// Extract TIMESTAMPTZ parameters from WHERE clause (returns microseconds)
+    ///
+    /// `field = x` yields one value and `field IN (x, y, ...)` yields every listed one.
     fn extract_qual_timestamptz(
         quals: &[bindings::supabase::wrappers::types::Qual],
         field: &str,
-    ) -> Option<i64> {
-        quals
+    ) -> Vec<i64> {
+        let cells = quals
             .iter()
             .find(|q| q.field() == field && q.operator() == "=")
-            .and_then(|q| match q.value() {
-                Value::Cell(Cell::Timestamptz(ts)) => Some(ts),
+            .map(|q| match q.value() {
+                Value::Cell(cell) => vec![cell],
+                Value::Array(cells) => cells,
+            })
+            .unwrap_or_default();
+        cells
+            .into_iter()
+            .filter_map(|cell| match cell {
+                Cell::Timestamptz(ts) => Some(ts),
                 _ => None,
             })
+            .collect()
     }
 
     /// Extract and validate location from WHERE clause
     fn extract_and_validate_location(
         quals: &[bindings::supabase::wrappers::types::Qual],
     ) -> Result<(f64, f64), FdwError> {
-        let latitude = Self::extract_qual_numeric(quals, "latitude").ok_or(
-            "WHERE clause must include 'latitude' between -90 and 90. \
-             Example: WHERE latitude = 52.52 AND longitude = 13.405",
-        )?;
-
-        let longitude = Self::extract_qual_numeric(quals, "longitude").ok_or(
-            "WHERE clause must include 'longitude' between -180 and 180. \
-             Example: WHERE latitude = 52.52 AND longitude = 13.405",
-        )?;
+        let has_coordinates = quals
+            .iter()
+            .any(|q| matches!(q.field().as_str(), "latitude" | "longitude"));
+        let (latitude, longitude) = match Self::extract_qual_string(quals, "location") {
+            Some(location) if !has_coordinates => {
+                let (longitude, latitude) = parse_wkt_point(&location).ok_or_else(|| {
+                    format!(
+                        "location must be a WKT point 'POINT(longitude latitude)', got '{}'. \
+                         Example: WHERE location = 'POINT(13.405 52.52)'",
+                        location
+                    )
+                })?;
+                (latitude, longitude)
+            }
+            _ => Self::extract_coordinates(quals)?,
+        };
 
         // Validate ranges
         if !(-90.0..=90.0).contains(&latitude) {
@@ -424,6 +1825,275 @@ impl OpenWeatherFdw {
         Ok((latitude, longitude))
     }
 
+    /// Extract the separate latitude/longitude quals from WHERE clause
+    fn extract_coordinates(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+    ) -> Result<(f64, f64), FdwError> {
+        // A qual that is present but not numeric gets its own error, not "must include"
+        let coordinate = |field: &str, bounds: &str| {
+            let (_, cell) = Self::find_qual(quals, field, &["="]).ok_or_else(|| {
+                format!(
+                    "WHERE clause must include '{}' between {}. \
+                     Example: WHERE latitude = 52.52 AND longitude = 13.405",
+                    field, bounds
+                )
+            })?;
+            Self::extract_qual_numeric(quals, field).ok_or_else(|| {
+                format!(
+                    "{} must be numeric, got {}. \
+                     Example: WHERE latitude = 52.52 AND longitude = 13.405",
+                    field,
+                    cell_type_name(&cell)
+                )
+            })
+        };
+
+        let latitude = coordinate("latitude", "-90 and 90")?;
+        let longitude = coordinate("longitude", "-180 and 180")?;
+
+        Ok((latitude, longitude))
+    }
+
+    /// Error for HTTP 200 responses that carry no data (e.g. remote ocean coordinates)
+    fn no_data_error(&self) -> FdwError {
+        format!(
+            "no data available for latitude={}, longitude={}. \
+             Set table option error_on_empty 'false' to return the partial response instead",
+            self.latitude, self.longitude
+        )
+    }
+
+    /// Table name declared by a generated 'create foreign table if not exists' statement
+    fn ddl_table_name(ddl: &str) -> Option<&str> {
+        ddl.split_once("if not exists ")
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+    }
+
+    /// Increment a stats metric under FDW_NAME and under a per-endpoint name
+    /// (e.g. "OpenWeatherFdw:hourly_forecast") so usage can be attributed per table
+    fn inc_stats(&self, metric: stats::Metric, value: i64) {
+        stats::inc_stats(FDW_NAME, metric, value);
+        if let Some(endpoint_type) = self.endpoint_type {
+            let endpoint_name = format!("{}:{}", FDW_NAME, endpoint_type.object_name());
+            stats::inc_stats(&endpoint_name, metric, value);
+        }
+    }
+
+    /// Check if a column is part of the current scan's projection
+    fn is_projected(&self, column: &str) -> bool {
+        self.projected_columns.iter().any(|c| c == column)
+    }
+
+    /// Check if the include table option left a /onecall section out of the response
+    fn excludes_section(&self, section: &str) -> bool {
+        self.onecall_exclude
+            .as_deref()
+            .is_some_and(|exclude| exclude.split(',').any(|s| s == section))
+    }
+
+    /// Alert count from a /onecall response; None when include left 'alerts' out, since
+    /// a missing array then says nothing about active alerts
+    fn onecall_alert_count(&self, resp_json: &JsonValue) -> Option<i64> {
+        (!self.excludes_section("alerts")).then(|| Self::count_alerts(resp_json))
+    }
+
+    /// Report (or, under strict_row_count, reject) a forecast array of unexpected length
+    fn check_row_count(&self, section: &str, actual: usize, expected: usize) -> FdwResult {
+        if actual == expected {
+            return Ok(());
+        }
+        let message = format!(
+            "OpenWeather returned {} '{}' entries, expected {} (latitude={}, longitude={})",
+            actual, section, expected, self.latitude, self.longitude
+        );
+        if self.strict_row_count {
+            return Err(format!(
+                "{}. Set table option strict_row_count 'false' to accept it",
+                message
+            ));
+        }
+        report_info(&message);
+        Ok(())
+    }
+
+    /// Parse the as_of option and check it lies between 1979-01-01 and now
+    fn validate_as_of(text: &str) -> Result<i64, FdwError> {
+        let as_of = Self::parse_timestamp(text).ok_or_else(|| {
+            format!(
+                "option 'as_of' must be Unix seconds or a timestamp like \
+                 '2024-10-28 00:00:00+00', got '{}'",
+                text
+            )
+        })?;
+        if as_of < MIN_HISTORICAL_DT {
+            return Err(format!(
+                "option 'as_of' must be on or after 1979-01-01 00:00:00+00 \
+                 (historical data starts in 1979), got unix time {}",
+                as_of
+            ));
+        }
+        let now = time::epoch_secs();
+        if as_of > now {
+            return Err(format!(
+                "option 'as_of' must not be in the future (got unix time {}, now is {})",
+                as_of, now
+            ));
+        }
+        Ok(as_of)
+    }
+
+    /// Check that a timemachine timestamp lies between 1979-01-01 and now
+    fn validate_historical_dt(dt: i64) -> FdwResult {
+        if dt < MIN_HISTORICAL_DT {
+            return Err(format!(
+                "observation_time must be on or after 1979-01-01 00:00:00+00 \
+                 (historical data starts in 1979), got unix time {}",
+                dt
+            ));
+        }
+        let now = time::epoch_secs();
+        if dt > now {
+            return Err(format!(
+                "observation_time must not be in the future (got unix time {}, now is {}). \
+                 Use hourly_forecast or daily_forecast for future weather",
+                dt, now
+            ));
+        }
+        Ok(())
+    }
+
+    /// API host root (base URL without the '/data/x.y' suffix), used by non-One Call APIs
+    fn api_root(&self) -> &str {
+        match self.base_url.find("/data/") {
+            Some(idx) => &self.base_url[..idx],
+            None => self.base_url.trim_end_matches('/'),
+        }
+    }
+
+    /// Percent-encode a query parameter value
+    fn url_encode(value: &str) -> String {
+        let mut encoded = String::with_capacity(value.len());
+        for byte in value.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b',' => {
+                    encoded.push(byte as char)
+                }
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+
+    /// Extract and validate the cities_in_bbox bounding box from WHERE clause
+    fn extract_bbox(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+    ) -> Result<BoundingBox, FdwError> {
+        let bound = |field: &str| {
+            Self::extract_qual_numeric(quals, field).ok_or(format!(
+                "WHERE clause must include '{}' for cities_in_bbox. \
+                 Example: WHERE min_lat = 52.3 AND min_lon = 13.0 AND max_lat = 52.7 AND max_lon = 13.8",
+                field
+            ))
+        };
+        let bbox = BoundingBox {
+            min_lat: bound("min_lat")?,
+            min_lon: bound("min_lon")?,
+            max_lat: bound("max_lat")?,
+            max_lon: bound("max_lon")?,
+            zoom: Self::extract_qual_i64(quals, "zoom", 1..=i64::MAX)?.unwrap_or(DEFAULT_BBOX_ZOOM),
+        };
+
+        for lat in [bbox.min_lat, bbox.max_lat] {
+            if !(-90.0..=90.0).contains(&lat) {
+                return Err(format!(
+                    "min_lat/max_lat must be between -90 and 90, got {}",
+                    lat
+                ));
+            }
+        }
+        for lon in [bbox.min_lon, bbox.max_lon] {
+            if !(-180.0..=180.0).contains(&lon) {
+                return Err(format!(
+                    "min_lon/max_lon must be between -180 and 180, got {}",
+                    lon
+                ));
+            }
+        }
+        if bbox.min_lat >= bbox.max_lat || bbox.min_lon >= bbox.max_lon {
+            return Err(format!(
+                "bounding box is empty: min_lat ({}) and min_lon ({}) must be below max_lat ({}) and max_lon ({})",
+                bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon
+            ));
+        }
+        Ok(bbox)
+    }
+
+    /// Extract the cities_group city IDs from WHERE clause, returned with the text
+    /// to echo in the city_ids column
+    ///
+    /// Accepts `city_ids = 'a,b,c'` or `city_id IN (a, b, c)` (or a single `city_id = a`).
+    fn extract_group_city_ids(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+    ) -> Result<(Vec<i64>, String), FdwError> {
+        let (ids, text) = if let Some(text) = Self::extract_qual_string(quals, "city_ids") {
+            let ids = text
+                .split(',')
+                .map(|id| {
+                    id.trim().parse::<i64>().map_err(|_| {
+                        format!("city_ids must be comma-separated integers, got '{}'", text)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            (ids, text)
+        } else {
+            let cells = quals
+                .iter()
+                .find(|q| q.field() == "city_id" && q.operator() == "=")
+                .map(|q| match q.value() {
+                    Value::Cell(cell) => vec![cell],
+                    Value::Array(cells) => cells,
+                })
+                .ok_or(
+                    "WHERE clause must include 'city_ids' or 'city_id' for cities_group. \
+                     Example: WHERE city_ids = '2950159,2643743' or WHERE city_id IN (2950159, 2643743)",
+                )?;
+            let ids = cells
+                .iter()
+                .map(|cell| match cell {
+                    Cell::I64(id) => Ok(*id),
+                    Cell::I32(id) => Ok(*id as i64),
+                    Cell::Numeric(id) | Cell::F64(id) if id.fract() == 0.0 => Ok(*id as i64),
+                    _ => Err("city_id values must be integers".to_string()),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let text = ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            (ids, text)
+        };
+
+        if ids.is_empty() || ids.len() > MAX_GROUP_CITY_IDS {
+            return Err(format!(
+                "cities_group accepts 1 to {} city IDs per query, got {}",
+                MAX_GROUP_CITY_IDS,
+                ids.len()
+            ));
+        }
+        Ok((ids, text))
+    }
+
+    /// Extract geocoding match limit from WHERE clause (defaults to the API maximum of 5)
+    fn extract_geocoding_limit(
+        quals: &[bindings::supabase::wrappers::types::Qual],
+    ) -> Result<i64, FdwError> {
+        Ok(
+            Self::extract_qual_i64(quals, "limit", 1..=MAX_GEOCODING_LIMIT)?
+                .unwrap_or(MAX_GEOCODING_LIMIT),
+        )
+    }
+
     /// Create HTTP request for OpenWeather API based on endpoint type
     fn create_request(&self) -> Result<http::Request, FdwError> {
         let endpoint_type = self
@@ -431,37 +2101,62 @@ impl OpenWeatherFdw {
             .ok_or("endpoint type not set - call begin_scan first")?;
 
         let api_path = endpoint_type.api_path();
+        let (latitude, longitude) = self.request_location();
 
         // Build URL with appropriate query parameters
         let url = match endpoint_type {
+            EndpointType::CurrentWeather if self.uses_legacy_weather(endpoint_type) => {
+                let location = match self.city_id {
+                    Some(city_id) => format!("id={}", city_id),
+                    None => format!("lat={}&lon={}", latitude, longitude),
+                };
+                format!(
+                    "{}{}?{}&appid={}&units={}&lang={}",
+                    self.api_root(),
+                    LEGACY_WEATHER_PATH,
+                    location,
+                    self.api_key,
+                    self.units,
+                    self.lang
+                )
+            }
             EndpointType::CurrentWeather
             | EndpointType::MinutelyForecast
+            | EndpointType::MinutelySummary
             | EndpointType::HourlyForecast
             | EndpointType::DailyForecast
-            | EndpointType::WeatherAlerts => {
-                format!(
+            | EndpointType::RainOutlook
+            | EndpointType::Forecast
+            | EndpointType::WeatherAlerts
+            | EndpointType::OneCallSummary => {
+                let mut url = format!(
                     "{}{}?lat={}&lon={}&appid={}&units={}&lang={}",
                     self.base_url,
                     api_path,
-                    self.latitude,
-                    self.longitude,
+                    latitude,
+                    longitude,
                     self.api_key,
                     self.units,
                     self.lang
-                )
+                );
+                // Skip the sections the include table option leaves out
+                if let Some(ref exclude) = self.onecall_exclude {
+                    url.push_str(&format!("&exclude={}", exclude));
+                }
+                url
             }
             EndpointType::HistoricalWeather => {
                 let dt = self.dt.ok_or(
                     "WHERE clause must include 'observation_time' for historical_weather.\n\
                      Use literal timestamp format: 'YYYY-MM-DD HH:MM:SS+00'\n\
-                     Example: WHERE observation_time = '2024-10-28 00:00:00+00'"
+                     Example: WHERE observation_time = '2024-10-28 00:00:00+00'",
                 )?;
                 format!(
                     "{}{}?lat={}&lon={}&dt={}&appid={}&units={}&lang={}",
                     self.base_url,
                     api_path,
-                    self.latitude,
-                    self.longitude,
+                    latitude,
+                    longitude,
                     dt,
                     self.api_key,
                     self.units,
@@ -472,14 +2167,14 @@ impl OpenWeatherFdw {
                 let date = self.date.as_ref().ok_or(
                     "WHERE clause must include 'summary_date' for daily_summary.\n\
                      Use literal date format: 'YYYY-MM-DD'\n\
-                     Example: WHERE summary_date = '2024-10-15'"
+                     Example: WHERE summary_date = '2024-10-15'",
                 )?;
                 let mut url = format!(
                     "{}{}?lat={}&lon={}&date={}&appid={}&units={}&lang={}",
                     self.base_url,
                     api_path,
-                    self.latitude,
-                    self.longitude,
+                    latitude,
+                    longitude,
                     date,
                     self.api_key,
                     self.units,
@@ -487,21 +2182,80 @@ impl OpenWeatherFdw {
                 );
                 // Add optional timezone_offset parameter
                 if let Some(ref tz) = self.timezone_offset {
-                    url.push_str(&format!("&tz={}", tz));
+                    url.push_str(&format!("&tz={}", Self::normalize_timezone_offset(tz)?));
                 }
                 url
             }
-            EndpointType::WeatherOverview => {
-                let mut url = format!(
-                    "{}{}?lat={}&lon={}&appid={}&units={}&lang={}",
-                    self.base_url,
+            EndpointType::Geocoding => {
+                let city_name = self.city_name.as_ref().ok_or(
+                    "WHERE clause must include 'city_name' for geocoding. \
+                     Example: WHERE city_name = 'Berlin,DE'",
+                )?;
+                format!(
+                    "{}{}?q={}&limit={}&appid={}",
+                    self.api_root(),
                     api_path,
-                    self.latitude,
-                    self.longitude,
+                    Self::url_encode(city_name),
+                    self.geocoding_limit,
+                    self.api_key
+                )
+            }
+            EndpointType::AirPollution => format!(
+                "{}{}?lat={}&lon={}&appid={}",
+                self.api_root(),
+                api_path,
+                latitude,
+                longitude,
+                self.api_key
+            ),
+            EndpointType::CitiesInBbox => {
+                let bbox = self
+                    .bbox
+                    .ok_or("bounding box not set - call begin_scan first")?;
+                format!(
+                    "{}{}?bbox={},{},{},{},{}&appid={}&units={}&lang={}",
+                    self.api_root(),
+                    api_path,
+                    bbox.min_lon,
+                    bbox.min_lat,
+                    bbox.max_lon,
+                    bbox.max_lat,
+                    bbox.zoom,
                     self.api_key,
                     self.units,
                     self.lang
-                );
+                )
+            }
+            EndpointType::CitiesGroup => format!(
+                "{}{}?id={}&appid={}&units={}&lang={}",
+                self.api_root(),
+                api_path,
+                self.group_city_ids
+                    .iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+                self.api_key,
+                self.units,
+                self.lang
+            ),
+            EndpointType::RequestMetadata | EndpointType::ApiUsageLog => {
+                return Err(format!(
+                    "{} does not call the OpenWeather API",
+                    endpoint_type.object_name()
+                ))
+            }
+            EndpointType::WeatherOverview => {
+                let mut url = format!(
+                    "{}{}?lat={}&lon={}&appid={}&units={}&lang={}",
+                    self.base_url,
+                    api_path,
+                    latitude,
+                    longitude,
+                    self.api_key,
+                    self.units,
+                    self.lang
+                );
                 // Add optional date parameter (defaults to today if omitted)
                 if let Some(ref date) = self.date {
                     url.push_str(&format!("&date={}", date));
@@ -510,14 +2264,384 @@ impl OpenWeatherFdw {
             }
         };
 
-        Ok(http::Request {
-            method: http::Method::Get,
+        // Escape hatch for API parameters this FDW does not know yet (appended verbatim)
+        let url = match self.extra_params {
+            Some(ref params) => format!("{}&{}", url, params),
+            None => url,
+        };
+
+        Ok(self.build_request(endpoint_type.http_method(), url, None))
+    }
+
+    /// Assemble a request with the configured headers; a body is sent as JSON
+    fn build_request(
+        &self,
+        method: http::Method,
+        url: String,
+        body: Option<JsonValue>,
+    ) -> http::Request {
+        let mut headers = self.headers.clone();
+        let body = match body {
+            Some(json) => {
+                headers.push(("content-type".to_owned(), "application/json".to_string()));
+                json.to_string()
+            }
+            None => String::default(),
+        };
+        http::Request {
+            method,
             url,
-            headers: self.headers.clone(),
-            body: String::default(),
+            headers,
+            body,
+        }
+    }
+
+    /// Send a request with the host function matching its method
+    fn send_request(req: &http::Request) -> http::HttpResult {
+        match req.method {
+            http::Method::Get => http::get(req),
+            http::Method::Post => http::post(req),
+            http::Method::Put => http::put(req),
+            http::Method::Patch => http::patch(req),
+            http::Method::Delete => http::delete(req),
+        }
+    }
+
+    /// Parse location matches from /geo/1.0/direct response
+    fn parse_geocoding(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let matches_arr = resp_json
+            .as_array()
+            .ok_or("expected array in geocoding response")?;
+
+        let mut matches = Vec::with_capacity(matches_arr.len());
+        for item in matches_arr {
+            matches.push(GeocodingRow {
+                location_name: item
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                latitude: item
+                    .get("lat")
+                    .and_then(|v| v.as_f64())
+                    .ok_or("missing lat in geocoding match")?,
+                longitude: item
+                    .get("lon")
+                    .and_then(|v| v.as_f64())
+                    .ok_or("missing lon in geocoding match")?,
+                country_code: item
+                    .get("country")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                state_name: item
+                    .get("state")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+            });
+        }
+
+        self.data = EndpointData::Geocoding {
+            city_name: self.city_name.clone().unwrap_or_default(),
+            limit: self.geocoding_limit,
+            matches,
+        };
+
+        report_info(&format!(
+            "Parsed {} geocoding matches",
+            self.data.row_count()
+        ));
+
+        Ok(())
+    }
+
+    /// Parse current air pollution from /data/2.5/air_pollution response
+    fn parse_air_pollution(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let entry = resp_json
+            .get("list")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first())
+            .ok_or("missing 'list' array in air_pollution response")?;
+
+        let components = entry
+            .get("components")
+            .ok_or("missing 'components' in air_pollution response")?;
+        // A pollutant the station does not measure is NULL, not 0
+        let component = |name: &str| components.get(name).and_then(|v| v.as_f64());
+
+        self.data = EndpointData::AirPollution {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            observation_time: entry
+                .get("dt")
+                .and_then(|v| v.as_i64())
+                .ok_or("missing 'dt' in air_pollution response")?,
+            aqi: entry
+                .get("main")
+                .and_then(|m| m.get("aqi"))
+                .and_then(|v| v.as_i64())
+                .ok_or("missing 'main.aqi' in air_pollution response")?,
+            co_ug_m3: component("co"),
+            no_ug_m3: component("no"),
+            no2_ug_m3: component("no2"),
+            o3_ug_m3: component("o3"),
+            so2_ug_m3: component("so2"),
+            pm2_5_ug_m3: component("pm2_5"),
+            pm10_ug_m3: component("pm10"),
+            nh3_ug_m3: component("nh3"),
+        };
+
+        report_info("Parsed air pollution data");
+
+        Ok(())
+    }
+
+    /// Parse the per-city current weather entries of a 2.5 'list' response
+    fn parse_city_list(resp_json: &JsonValue) -> Result<Vec<CityWeatherRow>, FdwError> {
+        // An area without cities may come back without a 'list' at all
+        let list = match resp_json.get("list").and_then(|v| v.as_array()) {
+            Some(list) => list,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut cities = Vec::with_capacity(list.len());
+        for item in list {
+            let main = item.get("main");
+            let wind = item.get("wind");
+            let coord = item.get("coord");
+            // box/city spells the coordinate keys 'Lat'/'Lon'
+            let coord_value = |keys: [&str; 2]| {
+                keys.iter()
+                    .find_map(|key| coord.and_then(|c| c.get(*key)).and_then(|v| v.as_f64()))
+            };
+            // Some list endpoints report pressure with decimals
+            let integer =
+                |v: &JsonValue| v.as_i64().or_else(|| v.as_f64().map(|f| f.round() as i64));
+            let weather = item
+                .get("weather")
+                .and_then(|v| v.as_array())
+                .and_then(|arr| arr.first());
+            let (weather_main, weather_description, weather_icon) = match weather {
+                Some(weather) => Self::weather_fields(weather),
+                None => (
+                    "Unknown".to_string(),
+                    "unknown".to_string(),
+                    "01d".to_string(),
+                ),
+            };
+
+            cities.push(CityWeatherRow {
+                city_id: item
+                    .get("id")
+                    .and_then(|v| v.as_i64())
+                    .ok_or("missing 'id' in city list entry")?,
+                city_name: item
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                latitude: coord_value(["lat", "Lat"])
+                    .ok_or("missing 'coord.lat' in city list entry")?,
+                longitude: coord_value(["lon", "Lon"])
+                    .ok_or("missing 'coord.lon' in city list entry")?,
+                observation_time: item
+                    .get("dt")
+                    .and_then(|v| v.as_i64())
+                    .ok_or("missing 'dt' in city list entry")?,
+                temperature_temp: main
+                    .and_then(|m| m.get("temp"))
+                    .and_then(|v| v.as_f64())
+                    .ok_or("missing 'main.temp' in city list entry")?,
+                apparent_temperature_temp: main
+                    .and_then(|m| m.get("feels_like"))
+                    .and_then(|v| v.as_f64()),
+                pressure_hpa: main.and_then(|m| m.get("pressure")).and_then(integer),
+                humidity_pct: main.and_then(|m| m.get("humidity")).and_then(integer),
+                cloud_cover_pct: item
+                    .get("clouds")
+                    .and_then(|c| c.get("all"))
+                    .and_then(integer),
+                wind_speed_m_s: wind.and_then(|w| w.get("speed")).and_then(|v| v.as_f64()),
+                wind_direction_deg: wind.and_then(|w| w.get("deg")).and_then(integer),
+                weather_condition: weather_main,
+                weather_condition_code: weather.and_then(Self::weather_code),
+                weather_description,
+                weather_icon_code: weather_icon,
+            });
+        }
+        Ok(cities)
+    }
+
+    /// Parse current weather for all cities in a bounding box from /data/2.5/box/city
+    fn parse_cities_in_bbox(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let bbox = self
+            .bbox
+            .ok_or("bounding box not set - call begin_scan first")?;
+        self.data = EndpointData::CitiesInBbox {
+            bbox,
+            cities: Self::parse_city_list(resp_json)?,
+        };
+
+        report_info(&format!(
+            "Parsed {} cities in bounding box",
+            self.data.row_count()
+        ));
+
+        Ok(())
+    }
+
+    /// Parse current weather for a list of city IDs from /data/2.5/group
+    fn parse_cities_group(&mut self, resp_json: &JsonValue) -> FdwResult {
+        self.data = EndpointData::CitiesGroup {
+            city_ids: self.group_city_ids_text.clone(),
+            cities: Self::parse_city_list(resp_json)?,
+        };
+
+        report_info(&format!("Parsed {} cities in group", self.data.row_count()));
+
+        Ok(())
+    }
+
+    /// Replace the API key in a request URL so it can be logged or exposed
+    fn redact_url(url: &str) -> String {
+        match url.find("appid=") {
+            Some(start) => {
+                let value_start = start + "appid=".len();
+                let value_end = url[value_start..]
+                    .find('&')
+                    .map_or(url.len(), |end| value_start + end);
+                format!("{}REDACTED{}", &url[..value_start], &url[value_end..])
+            }
+            None => url.to_string(),
+        }
+    }
+
+    /// Details of the last request, from this instance or persisted by an earlier one
+    fn stored_request_metadata(&self) -> Option<RequestMetadata> {
+        self.last_request.clone().or_else(|| {
+            stats::get_metadata(FDW_NAME)
+                .and_then(|raw| serde_json::from_str::<JsonValue>(&raw).ok())
+                .map(|json| RequestMetadata::from_json(&json))
         })
     }
 
+    /// Load the last request details from this instance, or as persisted by a previous scan
+    fn load_request_metadata(&mut self) -> FdwResult {
+        let metadata = self.stored_request_metadata();
+
+        self.data = EndpointData::RequestMetadata { metadata };
+
+        Ok(())
+    }
+
+    /// Number of entries in the /onecall 'alerts' array (absent when there are none)
+    fn count_alerts(resp_json: &JsonValue) -> i64 {
+        resp_json
+            .get("alerts")
+            .and_then(|v| v.as_array())
+            .map_or(0, |arr| arr.len() as i64)
+    }
+
+    /// Serialize a 'weather' conditions array for the weather_all column (skipped when
+    /// the column is not selected, since cloning and serializing is relatively costly)
+    fn weather_all_json(&self, weather_arr: &[JsonValue]) -> String {
+        if self.is_projected("weather_all") {
+            JsonValue::Array(weather_arr.to_vec()).to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Extract the location's shift from UTC in seconds from a /onecall response
+    fn extract_timezone_offset(resp_json: &JsonValue) -> i64 {
+        resp_json
+            .get("timezone_offset")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0)
+    }
+
+    /// Load api_usage_log counters persisted by earlier INSERTs
+    fn load_usage_log() -> Vec<UsageLogEntry> {
+        stats::get_metadata(USAGE_LOG_METADATA_KEY)
+            .and_then(|raw| serde_json::from_str::<JsonValue>(&raw).ok())
+            .and_then(|json| json.as_array().cloned())
+            .map(|entries| entries.iter().map(UsageLogEntry::from_json).collect())
+            .unwrap_or_default()
+    }
+
+    /// Persist api_usage_log counters for the companion read scan
+    fn save_usage_log(&self) {
+        let entries: Vec<JsonValue> = self.usage_log.iter().map(|e| e.to_json()).collect();
+        stats::set_metadata(
+            USAGE_LOG_METADATA_KEY,
+            &Some(JsonValue::Array(entries).to_string()),
+        );
+    }
+
+    /// Count one inserted api_usage_log row against its (endpoint, latitude, longitude)
+    fn record_usage(&mut self, row: &Row) -> FdwResult {
+        let mut endpoint = None;
+        let mut latitude = None;
+        let mut longitude = None;
+        for (col, cell) in row.cols().iter().zip(row.cells()) {
+            let number = match &cell {
+                Some(Cell::Numeric(n)) | Some(Cell::F64(n)) => Some(*n),
+                Some(Cell::F32(n)) => Some(*n as f64),
+                Some(Cell::I64(n)) => Some(*n as f64),
+                Some(Cell::I32(n)) => Some(*n as f64),
+                _ => None,
+            };
+            match (col.as_str(), cell) {
+                ("endpoint", Some(Cell::String(name))) => endpoint = Some(name),
+                ("latitude", _) => latitude = number,
+                ("longitude", _) => longitude = number,
+                _ => {}
+            }
+        }
+
+        let endpoint = endpoint.ok_or(
+            "api_usage_log INSERT must provide 'endpoint'. \
+             Example: INSERT INTO api_usage_log (endpoint, latitude, longitude) \
+             VALUES ('current_weather', 52.52, 13.405)",
+        )?;
+        // Reject typos so counters stay keyed by real endpoint names
+        EndpointType::from_object_name(&endpoint)?;
+
+        let now = time::epoch_secs();
+        match self
+            .usage_log
+            .iter_mut()
+            .find(|e| e.endpoint == endpoint && e.latitude == latitude && e.longitude == longitude)
+        {
+            Some(entry) => {
+                entry.logged_count += 1;
+                entry.last_logged_time = now;
+            }
+            None => {
+                if self.usage_log.len() >= MAX_USAGE_LOG_ENTRIES {
+                    if let Some(oldest) = self
+                        .usage_log
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, e)| e.last_logged_time)
+                        .map(|(idx, _)| idx)
+                    {
+                        self.usage_log.remove(oldest);
+                    }
+                }
+                self.usage_log.push(UsageLogEntry {
+                    endpoint,
+                    latitude,
+                    longitude,
+                    logged_count: 1,
+                    last_logged_time: now,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Parse current weather from /onecall response
     fn parse_current_weather(&mut self, resp_json: &JsonValue) -> FdwResult {
         let current = resp_json
@@ -540,35 +2664,33 @@ impl OpenWeatherFdw {
             .and_then(|v| v.as_f64())
             .ok_or("missing 'feels_like' in current")?;
 
-        let pressure = current
-            .get("pressure")
-            .and_then(|v| v.as_i64())
-            .ok_or("missing 'pressure' in current")?;
+        let pressure = self.optional_field(
+            current.get("pressure").and_then(|v| v.as_i64()),
+            "'pressure' in current",
+        )?;
 
         let humidity = current
             .get("humidity")
             .and_then(|v| v.as_i64())
             .ok_or("missing 'humidity' in current")?;
 
-        let dew_point = current
-            .get("dew_point")
-            .and_then(|v| v.as_f64())
-            .ok_or("missing 'dew_point' in current")?;
+        let dew_point = self.optional_field(
+            current.get("dew_point").and_then(|v| v.as_f64()),
+            "'dew_point' in current",
+        )?;
 
-        let uvi = current
-            .get("uvi")
-            .and_then(|v| v.as_f64())
-            .ok_or("missing 'uvi' in current")?;
+        let uvi = self.optional_field(
+            current.get("uvi").and_then(|v| v.as_f64()),
+            "'uvi' in current",
+        )?;
 
         let clouds = current
             .get("clouds")
             .and_then(|v| v.as_i64())
             .ok_or("missing 'clouds' in current")?;
 
-        let visibility = current
-            .get("visibility")
-            .and_then(|v| v.as_i64())
-            .ok_or("missing 'visibility' in current")?;
+        // visibility is sometimes omitted; emit NULL rather than failing the scan
+        let visibility = current.get("visibility").and_then(|v| v.as_i64());
 
         let wind_speed = current
             .get("wind_speed")
@@ -590,11 +2712,7 @@ impl OpenWeatherFdw {
 
         let weather = weather_arr.first().ok_or("weather array is empty")?;
 
-        let weather_main = weather
-            .get("main")
-            .and_then(|v| v.as_str())
-            .unwrap_or("Unknown")
-            .to_string();
+        let weather_main = Self::weather_condition(weather);
 
         let weather_description = weather
             .get("description")
@@ -610,14 +2728,20 @@ impl OpenWeatherFdw {
 
         // Store data
         self.data = EndpointData::CurrentWeather {
+            city_id: None,
             latitude: self.latitude,
             longitude: self.longitude,
-            timezone_name: resp_json
-                .get("timezone")
-                .and_then(|v| v.as_str())
-                .unwrap_or("UTC")
-                .to_string(),
+            timezone_name: Some(
+                resp_json
+                    .get("timezone")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("UTC")
+                    .to_string(),
+            ),
+            timezone_offset_seconds: Self::extract_timezone_offset(resp_json),
             observation_time: dt,
+            sunrise_time: current.get("sunrise").and_then(|v| v.as_i64()),
+            sunset_time: current.get("sunset").and_then(|v| v.as_i64()),
             temperature_temp: temp,
             apparent_temperature_temp: feels_like,
             pressure_hpa: pressure,
@@ -630,15 +2754,142 @@ impl OpenWeatherFdw {
             wind_direction_deg: wind_deg,
             wind_gust_speed_m_s: wind_gust,
             weather_condition: weather_main,
+            weather_condition_code: Self::weather_code(weather),
             weather_description,
             weather_icon_code: weather_icon,
+            weather_all: self.weather_all_json(weather_arr),
+            upcoming_temps: if self.is_projected("temperature_trend") {
+                resp_json
+                    .get("hourly")
+                    .and_then(|v| v.as_array())
+                    .map(|hours| {
+                        hours
+                            .iter()
+                            .take(TEMPERATURE_TREND_HOURS)
+                            .filter_map(|h| h.get("temp").and_then(|v| v.as_f64()))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            } else {
+                Vec::new()
+            },
+            active_alerts_count: self.onecall_alert_count(resp_json),
         };
 
         Ok(())
     }
 
-    /// Parse minutely forecast from /onecall response
-    fn parse_minutely_forecast(&mut self, resp_json: &JsonValue) -> FdwResult {
+    /// Parse current weather from the legacy /data/2.5/weather response (city_id queries)
+    fn parse_legacy_current_weather(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let main = resp_json
+            .get("main")
+            .ok_or("missing 'main' object in /weather response")?;
+        let wind = resp_json.get("wind");
+        let coord = resp_json
+            .get("coord")
+            .ok_or("missing 'coord' object in /weather response")?;
+
+        let weather_arr = resp_json
+            .get("weather")
+            .and_then(|v| v.as_array())
+            .ok_or("missing 'weather' array in /weather response")?;
+        let weather = weather_arr.first().ok_or("weather array is empty")?;
+
+        // Report the resolved location for city_id queries; coordinate queries keep the
+        // requested values so PostgreSQL's re-check of the quals still matches
+        if self.city_id.is_some() {
+            self.latitude = coord
+                .get("lat")
+                .and_then(|v| v.as_f64())
+                .ok_or("missing 'coord.lat' in /weather response")?;
+            self.longitude = coord
+                .get("lon")
+                .and_then(|v| v.as_f64())
+                .ok_or("missing 'coord.lon' in /weather response")?;
+        }
+
+        self.data = EndpointData::CurrentWeather {
+            city_id: resp_json
+                .get("id")
+                .and_then(|v| v.as_i64())
+                .or(self.city_id),
+            latitude: self.latitude,
+            longitude: self.longitude,
+            timezone_name: None,
+            // Legacy API reports the UTC shift (seconds) in 'timezone'
+            timezone_offset_seconds: resp_json
+                .get("timezone")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0),
+            observation_time: resp_json
+                .get("dt")
+                .and_then(|v| v.as_i64())
+                .ok_or("missing 'dt' in /weather response")?,
+            sunrise_time: resp_json
+                .get("sys")
+                .and_then(|sys| sys.get("sunrise"))
+                .and_then(|v| v.as_i64()),
+            sunset_time: resp_json
+                .get("sys")
+                .and_then(|sys| sys.get("sunset"))
+                .and_then(|v| v.as_i64()),
+            temperature_temp: main
+                .get("temp")
+                .and_then(|v| v.as_f64())
+                .ok_or("missing 'main.temp' in /weather response")?,
+            apparent_temperature_temp: main
+                .get("feels_like")
+                .and_then(|v| v.as_f64())
+                .ok_or("missing 'main.feels_like' in /weather response")?,
+            pressure_hpa: self.optional_field(
+                main.get("pressure").and_then(|v| v.as_i64()),
+                "'main.pressure' in /weather response",
+            )?,
+            humidity_pct: main
+                .get("humidity")
+                .and_then(|v| v.as_i64())
+                .ok_or("missing 'main.humidity' in /weather response")?,
+            dew_point_temp: None,
+            uv_index: None,
+            cloud_cover_pct: resp_json
+                .get("clouds")
+                .and_then(|c| c.get("all"))
+                .and_then(|v| v.as_i64())
+                .ok_or("missing 'clouds.all' in /weather response")?,
+            visibility_m: resp_json.get("visibility").and_then(|v| v.as_i64()),
+            wind_speed_m_s: wind
+                .and_then(|w| w.get("speed"))
+                .and_then(|v| v.as_f64())
+                .ok_or("missing 'wind.speed' in /weather response")?,
+            wind_direction_deg: wind
+                .and_then(|w| w.get("deg"))
+                .and_then(|v| v.as_i64())
+                .ok_or("missing 'wind.deg' in /weather response")?,
+            wind_gust_speed_m_s: wind.and_then(|w| w.get("gust")).and_then(|v| v.as_f64()),
+            weather_condition: Self::weather_condition(weather),
+            weather_condition_code: Self::weather_code(weather),
+            weather_description: weather
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            weather_icon_code: weather
+                .get("icon")
+                .and_then(|v| v.as_str())
+                .unwrap_or("01d")
+                .to_string(),
+            weather_all: self.weather_all_json(weather_arr),
+            upcoming_temps: Vec::new(),
+            active_alerts_count: None,
+        };
+
+        report_info("Parsed current weather data from legacy /weather endpoint");
+
+        Ok(())
+    }
+
+    /// Extract (timestamps, precipitation) pairs from the /onecall 'minutely' array
+    fn extract_minutely(resp_json: &JsonValue) -> Result<(Vec<i64>, Vec<f64>), FdwError> {
         let minutely_arr = resp_json
             .get("minutely")
             .and_then(|v| v.as_array())
@@ -659,6 +2910,14 @@ impl OpenWeatherFdw {
             }
         }
 
+        Ok((timestamps, precipitation))
+    }
+
+    /// Parse minutely forecast from /onecall response
+    fn parse_minutely_forecast(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let (timestamps, precipitation) = Self::extract_minutely(resp_json)?;
+        self.check_row_count("minutely", timestamps.len(), EXPECTED_MINUTELY_ROWS)?;
+
         self.data = EndpointData::MinutelyForecast {
             latitude: self.latitude,
             longitude: self.longitude,
@@ -666,7 +2925,7 @@ impl OpenWeatherFdw {
             precipitation_mm: precipitation,
         };
 
-        utils::report_info(&format!(
+        report_info(&format!(
             "Parsed {} minutely forecast data points",
             self.data.row_count()
         ));
@@ -674,12 +2933,155 @@ impl OpenWeatherFdw {
         Ok(())
     }
 
+    /// Parse minutely precipitation rollup from /onecall response
+    fn parse_minutely_summary(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let (_, precipitation) = Self::extract_minutely(resp_json)?;
+
+        let total_precip = precipitation.iter().sum();
+        let minutes_until_precip = precipitation
+            .iter()
+            .position(|&p| p > 0.0)
+            .map(|idx| idx as i64);
+        let peak_precip = precipitation.iter().copied().fold(0.0, f64::max);
+
+        self.data = EndpointData::MinutelySummary {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            total_precip_mm: total_precip,
+            minutes_until_precip,
+            peak_precip_mm: peak_precip,
+        };
+
+        report_info(&format!(
+            "Summarized {} minutely forecast data points",
+            precipitation.len()
+        ));
+
+        Ok(())
+    }
+
+    /// Parse a rain outlook from the /onecall hourly array: the first hour within the
+    /// horizon whose precipitation probability exceeds the threshold or that has rain
+    fn parse_rain_outlook(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let hourly_arr = resp_json
+            .get("hourly")
+            .and_then(|v| v.as_array())
+            .ok_or("missing 'hourly' array")?;
+
+        let mut rain_starts_at = None;
+        let mut max_pop: f64 = 0.0;
+        for item in hourly_arr.iter().take(self.rain_horizon_hours as usize) {
+            let pop = item.get("pop").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let rain_mm = item
+                .get("rain")
+                .and_then(|v| v.get("1h"))
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0);
+            max_pop = max_pop.max(pop);
+            if rain_starts_at.is_none() && (pop > self.rain_pop_threshold || rain_mm > 0.0) {
+                rain_starts_at = Some(
+                    item.get("dt")
+                        .and_then(|v| v.as_i64())
+                        .ok_or("missing dt")?,
+                );
+            }
+        }
+
+        self.data = EndpointData::RainOutlook {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            horizon_hours: self.rain_horizon_hours,
+            pop_threshold: self.rain_pop_threshold,
+            will_rain: rain_starts_at.is_some(),
+            rain_starts_at,
+            max_pop,
+        };
+
+        report_info(&format!(
+            "Summarized rain outlook over {} hours",
+            self.rain_horizon_hours
+        ));
+
+        Ok(())
+    }
+
+    /// Parse the unified forecast from the /onecall hourly and daily arrays
+    fn parse_forecast(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let mut rows = Vec::new();
+        for granularity in ["hourly", "daily"] {
+            let items = resp_json
+                .get(granularity)
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| format!("missing '{}' array", granularity))?;
+            for item in items {
+                rows.push(Self::forecast_row(granularity, item)?);
+            }
+        }
+
+        self.data = EndpointData::Forecast {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            rows,
+        };
+
+        report_info(&format!(
+            "Parsed {} unified forecast data points",
+            self.data.row_count()
+        ));
+
+        Ok(())
+    }
+
+    /// Build a unified forecast row from an hourly or daily /onecall entry
+    fn forecast_row(granularity: &'static str, item: &JsonValue) -> Result<ForecastRow, FdwError> {
+        let f64_field = |key: &str| item.get(key).and_then(|v| v.as_f64());
+        let i64_field = |key: &str| item.get(key).and_then(|v| v.as_i64());
+        // Hourly volumes sit under '1h'; daily volumes are plain numbers
+        let volume = |key: &str| {
+            item.get(key)
+                .and_then(|v| v.get("1h").or(Some(v)))
+                .and_then(|v| v.as_f64())
+        };
+        let temp = item.get("temp");
+        let daily_temp = |key: &str| temp.and_then(|t| t.get(key)).and_then(|v| v.as_f64());
+
+        let weather = item
+            .get("weather")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first())
+            .ok_or_else(|| format!("missing weather in {} entry", granularity))?;
+        let (weather_condition, weather_description, weather_icon_code) =
+            Self::weather_fields(weather);
+
+        Ok(ForecastRow {
+            granularity,
+            forecast_time: i64_field("dt").ok_or("missing dt")?,
+            temperature_temp: temp
+                .and_then(|t| t.as_f64().or_else(|| t.get("day").and_then(|v| v.as_f64())))
+                .ok_or_else(|| format!("missing temp in {} entry", granularity))?,
+            temperature_min_temp: daily_temp("min"),
+            temperature_max_temp: daily_temp("max"),
+            pressure_hpa: i64_field("pressure"),
+            humidity_pct: i64_field("humidity"),
+            wind_speed_m_s: f64_field("wind_speed"),
+            precipitation_probability: f64_field("pop"),
+            rain_volume_mm: volume("rain"),
+            snow_volume_mm: volume("snow"),
+            visibility_m: i64_field("visibility"),
+            weather_condition,
+            weather_condition_code: Self::weather_code(weather),
+            weather_description,
+            weather_icon_code,
+        })
+    }
+
     /// Parse hourly forecast from /onecall response
     fn parse_hourly_forecast(&mut self, resp_json: &JsonValue) -> FdwResult {
         let hourly_arr = resp_json
             .get("hourly")
             .and_then(|v| v.as_array())
             .ok_or("missing 'hourly' array")?;
+        self.check_row_count("hourly", hourly_arr.len(), EXPECTED_HOURLY_ROWS)?;
 
         let capacity = hourly_arr.len();
         let mut timestamps = Vec::with_capacity(capacity);
@@ -697,11 +3099,16 @@ impl OpenWeatherFdw {
         let mut pop = Vec::with_capacity(capacity);
         let mut rain_1h = Vec::with_capacity(capacity);
         let mut snow_1h = Vec::with_capacity(capacity);
+        let mut precip_type = Vec::with_capacity(capacity);
         let mut weather_main = Vec::with_capacity(capacity);
+        let mut weather_code = Vec::with_capacity(capacity);
         let mut weather_description = Vec::with_capacity(capacity);
         let mut weather_icon = Vec::with_capacity(capacity);
+        let mut weather_all = Vec::with_capacity(capacity);
+        // (hour index, condition) for every weather condition, when explode_weather is set
+        let mut exploded: Vec<(usize, &JsonValue)> = Vec::new();
 
-        for item in hourly_arr {
+        for (hour_idx, item) in hourly_arr.iter().enumerate() {
             timestamps.push(
                 item.get("dt")
                     .and_then(|v| v.as_i64())
@@ -718,9 +3125,7 @@ impl OpenWeatherFdw {
                     .ok_or("missing feels_like")?,
             );
             pressure.push(
-                item.get("pressure")
-                    .and_then(|v| v.as_i64())
-                    .ok_or("missing pressure")?,
+                self.optional_field(item.get("pressure").and_then(|v| v.as_i64()), "pressure")?,
             );
             humidity.push(
                 item.get("humidity")
@@ -728,25 +3133,16 @@ impl OpenWeatherFdw {
                     .ok_or("missing humidity")?,
             );
             dew_point.push(
-                item.get("dew_point")
-                    .and_then(|v| v.as_f64())
-                    .ok_or("missing dew_point")?,
-            );
-            uvi.push(
-                item.get("uvi")
-                    .and_then(|v| v.as_f64())
-                    .ok_or("missing uvi")?,
+                self.optional_field(item.get("dew_point").and_then(|v| v.as_f64()), "dew_point")?,
             );
+            uvi.push(self.optional_field(item.get("uvi").and_then(|v| v.as_f64()), "uvi")?);
             clouds.push(
                 item.get("clouds")
                     .and_then(|v| v.as_i64())
                     .ok_or("missing clouds")?,
             );
-            visibility.push(
-                item.get("visibility")
-                    .and_then(|v| v.as_i64())
-                    .ok_or("missing visibility")?,
-            );
+            // visibility is missing for some hours
+            visibility.push(item.get("visibility").and_then(|v| v.as_i64()));
             wind_speed.push(
                 item.get("wind_speed")
                     .and_then(|v| v.as_f64())
@@ -779,38 +3175,68 @@ impl OpenWeatherFdw {
                 .and_then(|v| v.as_f64());
             snow_1h.push(snow);
 
+            precip_type.push(Self::precipitation_type(
+                item.get("rain").is_some(),
+                item.get("snow").is_some(),
+            ));
+
             // Extract weather from weather[0]
             let weather_arr = item
                 .get("weather")
                 .and_then(|v| v.as_array())
                 .ok_or("missing weather array")?;
             let weather = weather_arr.first().ok_or("weather array is empty")?;
-            weather_main.push(
-                weather
-                    .get("main")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown")
-                    .to_string(),
-            );
-            weather_description.push(
-                weather
-                    .get("description")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("unknown")
-                    .to_string(),
-            );
-            weather_icon.push(
-                weather
-                    .get("icon")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("01d")
-                    .to_string(),
-            );
+            let (main, description, icon) = Self::weather_fields(weather);
+            weather_main.push(main);
+            weather_code.push(Self::weather_code(weather));
+            weather_description.push(description);
+            weather_icon.push(icon);
+            weather_all.push(self.weather_all_json(weather_arr));
+
+            if self.explode_weather {
+                exploded.extend(weather_arr.iter().map(|condition| (hour_idx, condition)));
+            }
         }
 
-        self.data = EndpointData::HourlyForecast {
-            latitude: self.latitude,
-            longitude: self.longitude,
+        // One row per weather condition per hour, repeating the hour's other values
+        if self.explode_weather {
+            let rows: Vec<usize> = exploded.iter().map(|(hour_idx, _)| *hour_idx).collect();
+            timestamps = Self::select_rows(&timestamps, &rows);
+            temps = Self::select_rows(&temps, &rows);
+            feels_like = Self::select_rows(&feels_like, &rows);
+            pressure = Self::select_rows(&pressure, &rows);
+            humidity = Self::select_rows(&humidity, &rows);
+            dew_point = Self::select_rows(&dew_point, &rows);
+            uvi = Self::select_rows(&uvi, &rows);
+            clouds = Self::select_rows(&clouds, &rows);
+            visibility = Self::select_rows(&visibility, &rows);
+            wind_speed = Self::select_rows(&wind_speed, &rows);
+            wind_deg = Self::select_rows(&wind_deg, &rows);
+            wind_gust = Self::select_rows(&wind_gust, &rows);
+            pop = Self::select_rows(&pop, &rows);
+            rain_1h = Self::select_rows(&rain_1h, &rows);
+            snow_1h = Self::select_rows(&snow_1h, &rows);
+            precip_type = Self::select_rows(&precip_type, &rows);
+            weather_all = Self::select_rows(&weather_all, &rows);
+            (
+                weather_main,
+                weather_code,
+                weather_description,
+                weather_icon,
+            ) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+            for (_, condition) in &exploded {
+                let (main, description, icon) = Self::weather_fields(condition);
+                weather_main.push(main);
+                weather_code.push(Self::weather_code(condition));
+                weather_description.push(description);
+                weather_icon.push(icon);
+            }
+        }
+
+        self.data = EndpointData::HourlyForecast {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            timezone_offset_seconds: Self::extract_timezone_offset(resp_json),
             forecast_time: timestamps,
             temperature_temp: temps,
             apparent_temperature_temp: feels_like,
@@ -826,12 +3252,15 @@ impl OpenWeatherFdw {
             precipitation_probability: pop,
             rain_volume_1h_mm: rain_1h,
             snow_volume_1h_mm: snow_1h,
+            precipitation_type: precip_type,
             weather_condition: weather_main,
+            weather_condition_code: weather_code,
             weather_description,
             weather_icon_code: weather_icon,
+            weather_all,
         };
 
-        utils::report_info(&format!(
+        report_info(&format!(
             "Parsed {} hourly forecast data points",
             self.data.row_count()
         ));
@@ -839,12 +3268,95 @@ impl OpenWeatherFdw {
         Ok(())
     }
 
+    /// Classify precipitation from which of the 'rain'/'snow' objects are present
+    fn precipitation_type(has_rain: bool, has_snow: bool) -> &'static str {
+        match (has_rain, has_snow) {
+            (true, true) => "mixed",
+            (true, false) => "rain",
+            (false, true) => "snow",
+            (false, false) => "none",
+        }
+    }
+
+    /// Policy for fields the API omits in edge responses (pressure, dew_point, uvi):
+    /// NULL when absent, or an error when the strict_fields table option is set
+    fn optional_field<T>(&self, value: Option<T>, name: &str) -> Result<Option<T>, FdwError> {
+        match value {
+            None if self.strict_fields => Err(format!("missing {}", name)),
+            value => Ok(value),
+        }
+    }
+
+    /// Extract the numeric condition id (e.g. 500 = light rain) from a 'weather' entry
+    fn weather_code(weather: &JsonValue) -> Option<i64> {
+        weather.get("id").and_then(|v| v.as_i64())
+    }
+
+    /// Canonical English condition group for weather_condition
+    ///
+    /// The lang parameter only translates `description`; `main` is always English, so
+    /// grouping by weather_condition is stable across languages. When `main` is
+    /// missing, the group is derived from the condition code instead.
+    fn weather_condition(weather: &JsonValue) -> String {
+        weather
+            .get("main")
+            .and_then(|v| v.as_str())
+            .filter(|main| !main.is_empty())
+            .or_else(|| Self::weather_code(weather).and_then(Self::condition_group))
+            .unwrap_or("Unknown")
+            .to_string()
+    }
+
+    /// OpenWeather condition group ("main") for a weather condition code
+    fn condition_group(code: i64) -> Option<&'static str> {
+        Some(match code {
+            200..=299 => "Thunderstorm",
+            300..=399 => "Drizzle",
+            500..=599 => "Rain",
+            600..=699 => "Snow",
+            701 => "Mist",
+            711 => "Smoke",
+            721 => "Haze",
+            731 | 761 => "Dust",
+            741 => "Fog",
+            751 => "Sand",
+            762 => "Ash",
+            771 => "Squall",
+            781 => "Tornado",
+            800 => "Clear",
+            801..=804 => "Clouds",
+            _ => return None,
+        })
+    }
+
+    /// Extract (main, description, icon) from a 'weather' conditions entry
+    fn weather_fields(weather: &JsonValue) -> (String, String, String) {
+        let field = |key: &str, default: &str| {
+            weather
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or(default)
+                .to_string()
+        };
+        (
+            Self::weather_condition(weather),
+            field("description", "unknown"),
+            field("icon", "01d"),
+        )
+    }
+
+    /// Pick values by row index (used to repeat per-hour values for exploded rows)
+    fn select_rows<T: Clone>(values: &[T], rows: &[usize]) -> Vec<T> {
+        rows.iter().map(|&idx| values[idx].clone()).collect()
+    }
+
     /// Parse daily forecast from /onecall response
     fn parse_daily_forecast(&mut self, resp_json: &JsonValue) -> FdwResult {
         let daily_arr = resp_json
             .get("daily")
             .and_then(|v| v.as_array())
             .ok_or("missing 'daily' array")?;
+        self.check_row_count("daily", daily_arr.len(), EXPECTED_DAILY_ROWS)?;
 
         let capacity = daily_arr.len();
         let mut timestamps = Vec::with_capacity(capacity);
@@ -875,8 +3387,10 @@ impl OpenWeatherFdw {
         let mut snow = Vec::with_capacity(capacity);
         let mut uvi = Vec::with_capacity(capacity);
         let mut weather_main = Vec::with_capacity(capacity);
+        let mut weather_code = Vec::with_capacity(capacity);
         let mut weather_description = Vec::with_capacity(capacity);
         let mut weather_icon = Vec::with_capacity(capacity);
+        let mut day_summary = Vec::with_capacity(capacity);
 
         for item in daily_arr {
             timestamps.push(
@@ -985,9 +3499,7 @@ impl OpenWeatherFdw {
             );
 
             pressure.push(
-                item.get("pressure")
-                    .and_then(|v| v.as_i64())
-                    .ok_or("missing pressure")?,
+                self.optional_field(item.get("pressure").and_then(|v| v.as_i64()), "pressure")?,
             );
             humidity.push(
                 item.get("humidity")
@@ -995,9 +3507,7 @@ impl OpenWeatherFdw {
                     .ok_or("missing humidity")?,
             );
             dew_point.push(
-                item.get("dew_point")
-                    .and_then(|v| v.as_f64())
-                    .ok_or("missing dew_point")?,
+                self.optional_field(item.get("dew_point").and_then(|v| v.as_f64()), "dew_point")?,
             );
             wind_speed.push(
                 item.get("wind_speed")
@@ -1022,10 +3532,11 @@ impl OpenWeatherFdw {
             );
             rain.push(item.get("rain").and_then(|v| v.as_f64()));
             snow.push(item.get("snow").and_then(|v| v.as_f64()));
-            uvi.push(
-                item.get("uvi")
-                    .and_then(|v| v.as_f64())
-                    .ok_or("missing uvi")?,
+            uvi.push(self.optional_field(item.get("uvi").and_then(|v| v.as_f64()), "uvi")?);
+            day_summary.push(
+                item.get("summary")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
             );
 
             // Extract weather from weather[0]
@@ -1034,13 +3545,8 @@ impl OpenWeatherFdw {
                 .and_then(|v| v.as_array())
                 .ok_or("missing weather array")?;
             let weather = weather_arr.first().ok_or("weather array is empty")?;
-            weather_main.push(
-                weather
-                    .get("main")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown")
-                    .to_string(),
-            );
+            weather_code.push(Self::weather_code(weather));
+            weather_main.push(Self::weather_condition(weather));
             weather_description.push(
                 weather
                     .get("description")
@@ -1060,6 +3566,7 @@ impl OpenWeatherFdw {
         self.data = EndpointData::DailyForecast {
             latitude: self.latitude,
             longitude: self.longitude,
+            timezone_offset_seconds: Self::extract_timezone_offset(resp_json),
             forecast_date: timestamps,
             sunrise_time: sunrise,
             sunset_time: sunset,
@@ -1088,11 +3595,13 @@ impl OpenWeatherFdw {
             snow_volume_mm: snow,
             uv_index: uvi,
             weather_condition: weather_main,
+            weather_condition_code: weather_code,
             weather_description,
             weather_icon_code: weather_icon,
+            day_summary_text: day_summary,
         };
 
-        utils::report_info(&format!(
+        report_info(&format!(
             "Parsed {} daily forecast data points",
             self.data.row_count()
         ));
@@ -1112,7 +3621,7 @@ impl OpenWeatherFdw {
                     longitude: self.longitude,
                     alerts: Vec::new(),
                 };
-                utils::report_info("No weather alerts for this location");
+                report_info("No weather alerts for this location");
                 return Ok(());
             }
         };
@@ -1120,6 +3629,20 @@ impl OpenWeatherFdw {
         let mut alerts = Vec::with_capacity(alerts_arr.len());
 
         for alert in alerts_arr {
+            let start = alert.get("start").and_then(|v| v.as_i64()).unwrap_or(0);
+            let end = alert.get("end").and_then(|v| v.as_i64()).unwrap_or(0);
+
+            // Skip alerts outside the alert_start_time / alert_end_time quals
+            // (e.g. alert_start_time <= T AND alert_end_time >= T for alerts active at T)
+            let in_range = |range: &Option<(Bound<i64>, Bound<i64>)>, ts: i64| {
+                range
+                    .as_ref()
+                    .is_none_or(|range| range.contains(&(ts * 1_000_000)))
+            };
+            if !in_range(&self.alert_start_range, start) || !in_range(&self.alert_end_range, end) {
+                continue;
+            }
+
             let sender_name = alert
                 .get("sender_name")
                 .and_then(|v| v.as_str())
@@ -1132,17 +3655,21 @@ impl OpenWeatherFdw {
                 .unwrap_or("Unknown")
                 .to_string();
 
-            let start = alert.get("start").and_then(|v| v.as_i64()).unwrap_or(0);
-            let end = alert.get("end").and_then(|v| v.as_i64()).unwrap_or(0);
-
             let description = alert
                 .get("description")
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string();
 
+            // Descriptions come in the issuing agency's language whatever the 'lang' query
+            // parameter; keep the language when the alert declares it
+            let description_lang = ["lang", "language"]
+                .iter()
+                .find_map(|key| alert.get(*key).and_then(|v| v.as_str()))
+                .map(|lang| lang.to_string());
+
             // Tags array
-            let tags = alert
+            let tags: Vec<String> = alert
                 .get("tags")
                 .and_then(|v| v.as_array())
                 .map(|arr| {
@@ -1153,23 +3680,111 @@ impl OpenWeatherFdw {
                 })
                 .unwrap_or_default();
 
+            let severity = if self.order_alerts || self.is_projected("alert_severity") {
+                AlertRow::severity_from_tags(&tags)
+            } else {
+                None
+            };
+
             alerts.push(AlertRow {
                 alert_sender_name: sender_name,
+                alert_event_normalized: fold_to_ascii(&event),
                 alert_event_type: event,
                 alert_start_time: start,
                 alert_end_time: end,
                 alert_description: description,
+                alert_description_lang: description_lang,
                 alert_tags: tags,
+                alert_severity: severity,
             });
         }
 
+        // Most severe first, then earliest start, so LIMIT 1 returns the worst alert
+        if self.order_alerts {
+            alerts.sort_by_key(|alert| (alert.severity_rank(), alert.alert_start_time));
+        }
+
         self.data = EndpointData::WeatherAlerts {
             latitude: self.latitude,
             longitude: self.longitude,
             alerts,
         };
 
-        utils::report_info(&format!("Parsed {} weather alerts", self.data.row_count()));
+        report_info(&format!("Parsed {} weather alerts", self.data.row_count()));
+
+        Ok(())
+    }
+
+    /// Parse a single-row dashboard snapshot from several /onecall sections
+    fn parse_onecall_summary(&mut self, resp_json: &JsonValue) -> FdwResult {
+        let current = resp_json
+            .get("current")
+            .ok_or("missing 'current' object in /onecall response")?;
+
+        let weather = current
+            .get("weather")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first());
+
+        // Minutely data is only available for some locations
+        let next_hour_precip = Self::extract_minutely(resp_json)
+            .ok()
+            .map(|(_, precipitation)| precipitation.iter().sum());
+
+        let today_temp = resp_json
+            .get("daily")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first())
+            .and_then(|day| day.get("temp"));
+
+        let active_alerts_count = self.onecall_alert_count(resp_json);
+
+        self.data = EndpointData::OneCallSummary {
+            latitude: self.latitude,
+            longitude: self.longitude,
+            timezone_name: resp_json
+                .get("timezone")
+                .and_then(|v| v.as_str())
+                .unwrap_or("UTC")
+                .to_string(),
+            observation_time: current
+                .get("dt")
+                .and_then(|v| v.as_i64())
+                .ok_or("missing 'dt' in current")?,
+            temperature_temp: current
+                .get("temp")
+                .and_then(|v| v.as_f64())
+                .ok_or("missing 'temp' in current")?,
+            apparent_temperature_temp: current
+                .get("feels_like")
+                .and_then(|v| v.as_f64())
+                .ok_or("missing 'feels_like' in current")?,
+            humidity_pct: current
+                .get("humidity")
+                .and_then(|v| v.as_i64())
+                .ok_or("missing 'humidity' in current")?,
+            wind_speed_m_s: current
+                .get("wind_speed")
+                .and_then(|v| v.as_f64())
+                .ok_or("missing 'wind_speed' in current")?,
+            weather_condition: weather
+                .map_or_else(|| "Unknown".to_string(), Self::weather_condition),
+            weather_description: weather
+                .and_then(|w| w.get("description"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            next_hour_precip_mm: next_hour_precip,
+            today_min_temp: today_temp
+                .and_then(|t| t.get("min"))
+                .and_then(|v| v.as_f64()),
+            today_max_temp: today_temp
+                .and_then(|t| t.get("max"))
+                .and_then(|v| v.as_f64()),
+            active_alerts_count,
+        };
+
+        report_info("Parsed One Call summary data");
 
         Ok(())
     }
@@ -1177,12 +3792,25 @@ impl OpenWeatherFdw {
     /// Parse historical weather from /onecall/timemachine response
     fn parse_historical_weather(&mut self, resp_json: &JsonValue) -> FdwResult {
         // CRITICAL: Extract from data[0] NOT flat response
-        let data_arr = resp_json
-            .get("data")
-            .and_then(|v| v.as_array())
-            .ok_or("missing 'data' array in timemachine response")?;
-
-        let historical = data_arr.first().ok_or("data array is empty")?;
+        // (API 2.5 timemachine responses carry a 'current' object instead)
+        let historical = match resp_json.get("data").and_then(|v| v.as_array()) {
+            Some(data_arr) => match data_arr.first() {
+                Some(historical) => historical,
+                // Expected for some remote locations and old dates: skip this timestamp
+                None if self.allow_empty => {
+                    report_info("Timemachine data array is empty, returning no row (allow_empty)");
+                    return Ok(());
+                }
+                None => {
+                    return Err("data array is empty; set table option allow_empty 'true' \
+                                to return no rows instead"
+                        .to_string())
+                }
+            },
+            None => resp_json
+                .get("current")
+                .ok_or("missing 'data' array in timemachine response")?,
+        };
 
         let dt = historical
             .get("dt")
@@ -1196,27 +3824,24 @@ impl OpenWeatherFdw {
             .get("feels_like")
             .and_then(|v| v.as_f64())
             .ok_or("missing feels_like")?;
-        let pressure = historical
-            .get("pressure")
-            .and_then(|v| v.as_i64())
-            .ok_or("missing pressure")?;
+        let pressure = self.optional_field(
+            historical.get("pressure").and_then(|v| v.as_i64()),
+            "pressure",
+        )?;
         let humidity = historical
             .get("humidity")
             .and_then(|v| v.as_i64())
             .ok_or("missing humidity")?;
-        let dew_point = historical
-            .get("dew_point")
-            .and_then(|v| v.as_f64())
-            .ok_or("missing dew_point")?;
+        let dew_point = self.optional_field(
+            historical.get("dew_point").and_then(|v| v.as_f64()),
+            "dew_point",
+        )?;
         let clouds = historical
             .get("clouds")
             .and_then(|v| v.as_i64())
             .ok_or("missing clouds")?;
         // visibility is optional in historical data (not always available)
-        let visibility = historical
-            .get("visibility")
-            .and_then(|v| v.as_i64())
-            .unwrap_or(10000); // Default to 10km if not provided
+        let visibility = historical.get("visibility").and_then(|v| v.as_i64());
         let wind_speed = historical
             .get("wind_speed")
             .and_then(|v| v.as_f64())
@@ -1232,11 +3857,7 @@ impl OpenWeatherFdw {
             .and_then(|v| v.as_array())
             .ok_or("missing weather array")?;
         let weather = weather_arr.first().ok_or("weather array is empty")?;
-        let weather_main = weather
-            .get("main")
-            .and_then(|v| v.as_str())
-            .unwrap_or("Unknown")
-            .to_string();
+        let weather_main = Self::weather_condition(weather);
         let weather_description = weather
             .get("description")
             .and_then(|v| v.as_str())
@@ -1248,9 +3869,7 @@ impl OpenWeatherFdw {
             .unwrap_or("01d")
             .to_string();
 
-        self.data = EndpointData::HistoricalWeather {
-            latitude: self.latitude,
-            longitude: self.longitude,
+        let observation = HistoricalObservation {
             observation_time: dt,
             temperature_temp: temp,
             apparent_temperature_temp: feels_like,
@@ -1262,11 +3881,24 @@ impl OpenWeatherFdw {
             wind_speed_m_s: wind_speed,
             wind_direction_deg: wind_deg,
             weather_condition: weather_main,
+            weather_condition_code: Self::weather_code(weather),
             weather_description,
             weather_icon_code: weather_icon,
         };
 
-        utils::report_info("Parsed historical weather data");
+        // Append so an observation_time IN list accumulates one row per timestamp
+        match &mut self.data {
+            EndpointData::HistoricalWeather { observations, .. } => observations.push(observation),
+            _ => {
+                self.data = EndpointData::HistoricalWeather {
+                    latitude: self.latitude,
+                    longitude: self.longitude,
+                    observations: vec![observation],
+                }
+            }
+        }
+
+        report_info("Parsed historical weather data");
 
         Ok(())
     }
@@ -1281,27 +3913,35 @@ impl OpenWeatherFdw {
             .get("lon")
             .and_then(|v| v.as_f64())
             .ok_or("missing lon")?;
-        let tz = resp_json
-            .get("tz")
-            .and_then(|v| v.as_str())
-            .unwrap_or("+00:00")
-            .to_string();
+        // Echo a queried offset as written ("+0200") so PostgreSQL's qual recheck
+        // still matches the API's normalised "+02:00"
+        let tz = match self.timezone_offset {
+            Some(ref tz) => tz.clone(),
+            None => resp_json
+                .get("tz")
+                .and_then(|v| v.as_str())
+                .unwrap_or("+00:00")
+                .to_string(),
+        };
         let date = resp_json
             .get("date")
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string();
+        // Trust the units the API echoes; fall back to what was requested, not metric
         let units = resp_json
             .get("units")
             .and_then(|v| v.as_str())
-            .unwrap_or("metric")
-            .to_string();
+            .map(str::to_string)
+            .unwrap_or_else(|| self.units.clone());
 
         // Extract nested temperature object
-        let temp_obj = resp_json
-            .get("temperature")
-            .and_then(|v| v.as_object())
-            .ok_or("missing temperature object")?;
+        let temp_obj = match resp_json.get("temperature").and_then(|v| v.as_object()) {
+            Some(obj) if !obj.is_empty() => obj,
+            _ if self.error_on_empty => return Err(self.no_data_error()),
+            Some(obj) => obj,
+            None => return Err("missing temperature object".to_owned()),
+        };
         let temp_min = temp_obj
             .get("min")
             .and_then(|v| v.as_f64())
@@ -1377,7 +4017,7 @@ impl OpenWeatherFdw {
             .and_then(|v| v.as_f64())
             .unwrap_or(0.0);
 
-        self.data = EndpointData::DailySummary {
+        let summary = DailySummaryRow {
             latitude: lat,
             longitude: lon,
             timezone_offset: tz,
@@ -1395,9 +4035,20 @@ impl OpenWeatherFdw {
             precipitation_total_mm: precipitation_total,
             wind_max_speed_m_s: wind_max_speed,
             wind_max_direction_deg: wind_max_direction,
+            api_message: Self::api_message(resp_json),
         };
 
-        utils::report_info("Parsed daily summary data");
+        // Append so a date range accumulates one row per day
+        match &mut self.data {
+            EndpointData::DailySummary { summaries } => summaries.push(summary),
+            _ => {
+                self.data = EndpointData::DailySummary {
+                    summaries: vec![summary],
+                }
+            }
+        }
+
+        report_info("Parsed daily summary data");
 
         Ok(())
     }
@@ -1422,16 +4073,30 @@ impl OpenWeatherFdw {
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string();
+        // Trust the units the API echoes; fall back to what was requested, not metric
         let units = resp_json
             .get("units")
             .and_then(|v| v.as_str())
-            .unwrap_or("metric")
-            .to_string();
+            .map(str::to_string)
+            .unwrap_or_else(|| self.units.clone());
         let weather_overview = resp_json
             .get("weather_overview")
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string();
+        if weather_overview.trim().is_empty() && self.error_on_empty {
+            return Err(self.no_data_error());
+        }
+
+        // Heuristic temperature mentions, only when explicitly enabled
+        let (mentioned_high_temp, mentioned_low_temp) = if self.extract_overview_metrics {
+            (
+                mentioned_temperature(&weather_overview, &["high", "maximum", "max "]),
+                mentioned_temperature(&weather_overview, &["low", "minimum", "min "]),
+            )
+        } else {
+            (None, None)
+        };
 
         self.data = EndpointData::WeatherOverview {
             latitude: lat,
@@ -1440,30 +4105,167 @@ impl OpenWeatherFdw {
             overview_date: date,
             unit_system: units,
             weather_overview,
+            mentioned_high_temp,
+            mentioned_low_temp,
+            api_message: Self::api_message(resp_json),
         };
 
-        utils::report_info("Parsed weather overview data");
+        report_info("Parsed weather overview data");
 
         Ok(())
     }
 
+    /// Convert a wind speed in the active unit system (m/s, or mph for imperial) to knots
+    fn wind_speed_knots(&self, speed: f64) -> f64 {
+        match self.units.as_str() {
+            "imperial" => speed * KNOTS_PER_MPH,
+            _ => speed * KNOTS_PER_M_S,
+        }
+    }
+
+    /// OpenWeather's 0-1 probability of precipitation (pop) as a whole percent
+    fn probability_pct(pop: f64) -> i64 {
+        (pop * 100.0).round() as i64
+    }
+
+    /// Illuminated share of the moon's disc in percent for a phase fraction
+    /// (0 and 1 = new moon at 0%, 0.5 = full moon at 100%)
+    fn moon_illumination_pct(fraction: f64) -> f64 {
+        (1.0 - (2.0 * std::f64::consts::PI * fraction).cos()) / 2.0 * 100.0
+    }
+
+    /// OpenWeather icon image URL for an icon code (e.g. "10d") at the icon_size option
+    fn weather_icon_url(&self, code: &str) -> String {
+        let suffix = match self.icon_size.as_str() {
+            "1x" => "",
+            "4x" => "@4x",
+            _ => "@2x",
+        };
+        format!("{}/{}{}.png", WEATHER_ICON_BASE_URL, code, suffix)
+    }
+
+    /// Map OpenWeather's moon phase fraction to a named phase
+    /// (0 and 1 = new moon, 0.25 = first quarter, 0.5 = full moon, 0.75 = last quarter)
+    fn moon_phase_name(fraction: f64) -> &'static str {
+        match fraction {
+            f if f <= 0.0 || f >= 1.0 => "New Moon",
+            f if f < 0.25 => "Waxing Crescent",
+            0.25 => "First Quarter",
+            f if f < 0.5 => "Waxing Gibbous",
+            0.5 => "Full Moon",
+            f if f < 0.75 => "Waning Gibbous",
+            0.75 => "Last Quarter",
+            _ => "Waning Crescent",
+        }
+    }
+
+    /// Classify the temperature trend by exponentially smoothing the upcoming hourly
+    /// temperatures (starting from the current one) and comparing the result to now
+    fn temperature_trend(current: f64, upcoming: &[f64]) -> Option<&'static str> {
+        if upcoming.is_empty() {
+            return None;
+        }
+        let smoothed = upcoming.iter().fold(current, |acc, &t| {
+            TEMPERATURE_TREND_ALPHA * t + (1.0 - TEMPERATURE_TREND_ALPHA) * acc
+        });
+        Some(match smoothed - current {
+            d if d >= TEMPERATURE_TREND_THRESHOLD => "rising",
+            d if d <= -TEMPERATURE_TREND_THRESHOLD => "falling",
+            _ => "steady",
+        })
+    }
+
+    /// Day/night flag from a weather icon code ('01d' = day, '01n' = night)
+    fn is_daytime(icon_code: &str) -> Option<bool> {
+        match icon_code.chars().last() {
+            Some('d') => Some(true),
+            Some('n') => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Map a UV index to its WHO risk band (values are rounded to whole numbers first)
+    fn uv_index_risk(uvi: f64) -> &'static str {
+        match uvi.round() as i64 {
+            i64::MIN..=2 => "Low",
+            3..=5 => "Moderate",
+            6..=7 => "High",
+            8..=10 => "Very High",
+            _ => "Extreme",
+        }
+    }
+
+    /// Map OpenWeather's Air Quality Index (1-5) to its qualitative name
+    fn aqi_label(aqi: i64) -> Option<&'static str> {
+        match aqi {
+            1 => Some("Good"),
+            2 => Some("Fair"),
+            3 => Some("Moderate"),
+            4 => Some("Poor"),
+            5 => Some("Very Poor"),
+            _ => None,
+        }
+    }
+
     /// Convert OpenWeather data at current row index to PostgreSQL cell
-    fn get_cell_value(&self, tgt_col: &Column) -> Result<Option<Cell>, FdwError> {
-        let tgt_col_name = tgt_col.name();
-        let row_idx = self.current_row;
+    fn get_cell_value(&self, tgt_col_name: &str) -> Result<Option<Cell>, FdwError> {
+        let row_count = self.data.row_count();
 
         // Check if we have data at current index
-        if row_idx >= self.data.row_count() {
+        if self.current_row >= row_count {
             return Err("row index out of bounds".to_owned());
         }
 
+        // Walk the vectors backwards when a descending sort was pushed down
+        let row_idx = if self.descending {
+            row_count - 1 - self.current_row
+        } else {
+            self.current_row
+        };
+
+        // fetched_at is available on every table (see the with_fetch_time import option)
+        if tgt_col_name == "fetched_at" {
+            return Ok(self.fetched_at.map(|t| Cell::Timestamptz(t * 1_000_000)));
+        }
+
+        // weather_icon_url is built from weather_icon_code on every table that has one
+        if tgt_col_name == "weather_icon_url" {
+            return match self.get_cell_value("weather_icon_code") {
+                Ok(Some(Cell::String(code))) if !code.is_empty() => {
+                    Ok(Some(Cell::String(self.weather_icon_url(&code))))
+                }
+                Ok(_) => Ok(None),
+                Err(_) => Err(format!(
+                    "unknown column 'weather_icon_url' for {} endpoint",
+                    self.endpoint_type.map_or("unknown", |e| e.object_name())
+                )),
+            };
+        }
+
+        // Weather tables echo a queried city_name or location so PostgreSQL's qual
+        // recheck matches
+        if self
+            .endpoint_type
+            .is_some_and(|endpoint_type| endpoint_type.requires_location())
+        {
+            match tgt_col_name {
+                "city_name" => return Ok(self.city_name.clone().map(Cell::String)),
+                "location" => return Ok(self.location.clone().map(Cell::String)),
+                _ => {}
+            }
+        }
+
         // Map column name to data based on endpoint type
         let cell = match &self.data {
             EndpointData::CurrentWeather {
+                city_id,
                 latitude,
                 longitude,
                 timezone_name,
+                timezone_offset_seconds,
                 observation_time,
+                sunrise_time,
+                sunset_time,
                 temperature_temp,
                 apparent_temperature_temp,
                 pressure_hpa,
@@ -1476,27 +4278,58 @@ impl OpenWeatherFdw {
                 wind_direction_deg,
                 wind_gust_speed_m_s,
                 weather_condition,
+                weather_condition_code,
                 weather_description,
                 weather_icon_code,
-            } => match tgt_col_name.as_str() {
+                weather_all,
+                upcoming_temps,
+                active_alerts_count,
+            } => match tgt_col_name {
+                "city_id" => city_id.map(Cell::I64),
                 "latitude" => Some(Cell::Numeric(*latitude)),
                 "longitude" => Some(Cell::Numeric(*longitude)),
-                "timezone_name" => Some(Cell::String(timezone_name.clone())),
+                "timezone_name" => timezone_name.clone().map(Cell::String),
+                "timezone_offset_seconds" => Some(Cell::I64(*timezone_offset_seconds)),
                 "observation_time" => Some(Cell::Timestamptz(observation_time * 1_000_000)),
+                "observation_time_local" => Some(Cell::Timestamp(
+                    (observation_time + timezone_offset_seconds) * 1_000_000,
+                )),
+                // Seconds between the observation and now, per the host clock
+                "forecast_age_seconds" => Some(Cell::I64(time::epoch_secs() - observation_time)),
+                "sunrise_time" => sunrise_time.map(|v| Cell::Timestamptz(v * 1_000_000)),
+                "sunset_time" => sunset_time.map(|v| Cell::Timestamptz(v * 1_000_000)),
                 "temperature_temp" => Some(Cell::Numeric(*temperature_temp)),
                 "apparent_temperature_temp" => Some(Cell::Numeric(*apparent_temperature_temp)),
-                "pressure_hpa" => Some(Cell::Numeric(*pressure_hpa as f64)),
-                "humidity_pct" => Some(Cell::Numeric(*humidity_pct as f64)),
-                "dew_point_temp" => Some(Cell::Numeric(*dew_point_temp)),
-                "uv_index" => Some(Cell::Numeric(*uv_index)),
-                "cloud_cover_pct" => Some(Cell::Numeric(*cloud_cover_pct as f64)),
-                "visibility_m" => Some(Cell::Numeric(*visibility_m as f64)),
+                // How much warmer (+) or colder (-) it feels, in the query's units
+                "apparent_temp_delta" => {
+                    Some(Cell::Numeric(apparent_temperature_temp - temperature_temp))
+                }
+                "pressure_hpa" => pressure_hpa.map(Cell::I64),
+                "pressure_inhg" => pressure_hpa.map(|p| Cell::Numeric(p as f64 * INHG_PER_HPA)),
+                "humidity_pct" => Some(Cell::I64(*humidity_pct)),
+                "dew_point_temp" => dew_point_temp.map(Cell::Numeric),
+                "uv_index" => uv_index.map(Cell::Numeric),
+                "uv_index_risk" => {
+                    uv_index.map(|v| Cell::String(Self::uv_index_risk(v).to_string()))
+                }
+                "cloud_cover_pct" => Some(Cell::I64(*cloud_cover_pct)),
+                "visibility_m" => visibility_m.map(Cell::I64),
+                "visibility_km" => visibility_m.map(|v| Cell::Numeric(v as f64 / 1000.0)),
+                "visibility_mi" => visibility_m.map(|v| Cell::Numeric(v as f64 / METERS_PER_MILE)),
                 "wind_speed_m_s" => Some(Cell::Numeric(*wind_speed_m_s)),
-                "wind_direction_deg" => Some(Cell::Numeric(*wind_direction_deg as f64)),
+                "wind_speed_kt" => Some(Cell::Numeric(self.wind_speed_knots(*wind_speed_m_s))),
+                "wind_direction_deg" => Some(Cell::I64(*wind_direction_deg)),
                 "wind_gust_speed_m_s" => wind_gust_speed_m_s.map(Cell::Numeric),
                 "weather_condition" => Some(Cell::String(weather_condition.clone())),
+                "weather_condition_code" => weather_condition_code.map(Cell::I64),
                 "weather_description" => Some(Cell::String(weather_description.clone())),
                 "weather_icon_code" => Some(Cell::String(weather_icon_code.clone())),
+                "is_daytime" => Self::is_daytime(weather_icon_code).map(Cell::Bool),
+                "weather_all" => Some(Cell::Json(weather_all.clone())),
+                "temperature_trend" => Self::temperature_trend(*temperature_temp, upcoming_temps)
+                    .map(|trend| Cell::String(trend.to_string())),
+                "active_alerts_count" => active_alerts_count.map(Cell::I64),
+                "has_active_alerts" => active_alerts_count.map(|count| Cell::Bool(count > 0)),
                 _ => {
                     return Err(format!(
                         "unknown column '{}' for current_weather endpoint",
@@ -1510,7 +4343,7 @@ impl OpenWeatherFdw {
                 longitude,
                 forecast_time,
                 precipitation_mm,
-            } => match tgt_col_name.as_str() {
+            } => match tgt_col_name {
                 "latitude" => Some(Cell::Numeric(*latitude)),
                 "longitude" => Some(Cell::Numeric(*longitude)),
                 "forecast_time" => forecast_time
@@ -1525,9 +4358,88 @@ impl OpenWeatherFdw {
                 }
             },
 
+            EndpointData::MinutelySummary {
+                latitude,
+                longitude,
+                total_precip_mm,
+                minutes_until_precip,
+                peak_precip_mm,
+            } => match tgt_col_name {
+                "latitude" => Some(Cell::Numeric(*latitude)),
+                "longitude" => Some(Cell::Numeric(*longitude)),
+                "total_precip_mm" => Some(Cell::Numeric(*total_precip_mm)),
+                "minutes_until_precip" => minutes_until_precip.map(Cell::I64),
+                "peak_precip_mm" => Some(Cell::Numeric(*peak_precip_mm)),
+                _ => {
+                    return Err(format!(
+                        "unknown column '{}' for minutely_summary endpoint",
+                        tgt_col_name
+                    ))
+                }
+            },
+
+            EndpointData::RainOutlook {
+                latitude,
+                longitude,
+                horizon_hours,
+                pop_threshold,
+                will_rain,
+                rain_starts_at,
+                max_pop,
+            } => match tgt_col_name {
+                "latitude" => Some(Cell::Numeric(*latitude)),
+                "longitude" => Some(Cell::Numeric(*longitude)),
+                "horizon_hours" => Some(Cell::I64(*horizon_hours)),
+                "pop_threshold" => Some(Cell::Numeric(*pop_threshold)),
+                "will_rain" => Some(Cell::Bool(*will_rain)),
+                "rain_starts_at" => rain_starts_at.map(|v| Cell::Timestamptz(v * 1_000_000)),
+                "max_pop" => Some(Cell::Numeric(*max_pop)),
+                _ => {
+                    return Err(format!(
+                        "unknown column '{}' for rain_outlook endpoint",
+                        tgt_col_name
+                    ))
+                }
+            },
+
+            EndpointData::Forecast {
+                latitude,
+                longitude,
+                rows,
+            } => {
+                let row = rows.get(row_idx).ok_or("forecast index out of bounds")?;
+                match tgt_col_name {
+                    "latitude" => Some(Cell::Numeric(*latitude)),
+                    "longitude" => Some(Cell::Numeric(*longitude)),
+                    "granularity" => Some(Cell::String(row.granularity.to_string())),
+                    "forecast_time" => Some(Cell::Timestamptz(row.forecast_time * 1_000_000)),
+                    "temperature_temp" => Some(Cell::Numeric(row.temperature_temp)),
+                    "temperature_min_temp" => row.temperature_min_temp.map(Cell::Numeric),
+                    "temperature_max_temp" => row.temperature_max_temp.map(Cell::Numeric),
+                    "pressure_hpa" => row.pressure_hpa.map(Cell::I64),
+                    "humidity_pct" => row.humidity_pct.map(Cell::I64),
+                    "wind_speed_m_s" => row.wind_speed_m_s.map(Cell::Numeric),
+                    "precipitation_probability" => row.precipitation_probability.map(Cell::Numeric),
+                    "rain_volume_mm" => row.rain_volume_mm.map(Cell::Numeric),
+                    "snow_volume_mm" => row.snow_volume_mm.map(Cell::Numeric),
+                    "visibility_m" => row.visibility_m.map(Cell::I64),
+                    "weather_condition" => Some(Cell::String(row.weather_condition.clone())),
+                    "weather_condition_code" => row.weather_condition_code.map(Cell::I64),
+                    "weather_description" => Some(Cell::String(row.weather_description.clone())),
+                    "weather_icon_code" => Some(Cell::String(row.weather_icon_code.clone())),
+                    _ => {
+                        return Err(format!(
+                            "unknown column '{}' for forecast endpoint",
+                            tgt_col_name
+                        ))
+                    }
+                }
+            }
+
             EndpointData::HourlyForecast {
                 latitude,
                 longitude,
+                timezone_offset_seconds,
                 forecast_time,
                 temperature_temp,
                 apparent_temperature_temp,
@@ -1543,52 +4455,96 @@ impl OpenWeatherFdw {
                 precipitation_probability,
                 rain_volume_1h_mm,
                 snow_volume_1h_mm,
+                precipitation_type,
                 weather_condition,
+                weather_condition_code,
                 weather_description,
                 weather_icon_code,
-            } => match tgt_col_name.as_str() {
+                weather_all,
+            } => match tgt_col_name {
                 "latitude" => Some(Cell::Numeric(*latitude)),
                 "longitude" => Some(Cell::Numeric(*longitude)),
+                "timezone_offset_seconds" => Some(Cell::I64(*timezone_offset_seconds)),
                 "forecast_time" => forecast_time
                     .get(row_idx)
                     .map(|&v| Cell::Timestamptz(v * 1_000_000)),
+                "forecast_time_local" => forecast_time
+                    .get(row_idx)
+                    .map(|&v| Cell::Timestamp((v + timezone_offset_seconds) * 1_000_000)),
                 "temperature_temp" => temperature_temp.get(row_idx).map(|&v| Cell::Numeric(v)),
                 "apparent_temperature_temp" => apparent_temperature_temp
                     .get(row_idx)
                     .map(|&v| Cell::Numeric(v)),
-                "pressure_hpa" => pressure_hpa.get(row_idx).map(|&v| Cell::Numeric(v as f64)),
-                "humidity_pct" => humidity_pct.get(row_idx).map(|&v| Cell::Numeric(v as f64)),
-                "dew_point_temp" => dew_point_temp.get(row_idx).map(|&v| Cell::Numeric(v)),
-                "uv_index" => uv_index.get(row_idx).map(|&v| Cell::Numeric(v)),
-                "cloud_cover_pct" => cloud_cover_pct
+                "apparent_temp_delta" => apparent_temperature_temp
+                    .get(row_idx)
+                    .zip(temperature_temp.get(row_idx))
+                    .map(|(feels, temp)| Cell::Numeric(feels - temp)),
+                "pressure_hpa" => pressure_hpa.get(row_idx).and_then(|&v| v.map(Cell::I64)),
+                "pressure_inhg" => pressure_hpa
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|p| Cell::Numeric(p as f64 * INHG_PER_HPA))),
+                "humidity_pct" => humidity_pct.get(row_idx).map(|&v| Cell::I64(v)),
+                "dew_point_temp" => dew_point_temp
                     .get(row_idx)
-                    .map(|&v| Cell::Numeric(v as f64)),
-                "visibility_m" => visibility_m.get(row_idx).map(|&v| Cell::Numeric(v as f64)),
+                    .and_then(|&v| v.map(Cell::Numeric)),
+                "uv_index" => uv_index.get(row_idx).and_then(|&v| v.map(Cell::Numeric)),
+                "uv_index_risk" => uv_index
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|v| Cell::String(Self::uv_index_risk(v).to_string()))),
+                "cloud_cover_pct" => cloud_cover_pct.get(row_idx).map(|&v| Cell::I64(v)),
+                "visibility_m" => visibility_m.get(row_idx).and_then(|&v| v.map(Cell::I64)),
+                "visibility_km" => visibility_m
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|v| Cell::Numeric(v as f64 / 1000.0))),
+                "visibility_mi" => visibility_m
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|v| Cell::Numeric(v as f64 / METERS_PER_MILE))),
                 "wind_speed_m_s" => wind_speed_m_s.get(row_idx).map(|&v| Cell::Numeric(v)),
-                "wind_direction_deg" => wind_direction_deg
+                "wind_speed_kt" => wind_speed_m_s
                     .get(row_idx)
-                    .map(|&v| Cell::Numeric(v as f64)),
+                    .map(|&v| Cell::Numeric(self.wind_speed_knots(v))),
+                "wind_direction_deg" => wind_direction_deg.get(row_idx).map(|&v| Cell::I64(v)),
                 "wind_gust_speed_m_s" => wind_gust_speed_m_s
                     .get(row_idx)
                     .and_then(|&v| v.map(Cell::Numeric)),
                 "precipitation_probability" => precipitation_probability
                     .get(row_idx)
                     .map(|&v| Cell::Numeric(v)),
+                "precipitation_probability_pct" => precipitation_probability
+                    .get(row_idx)
+                    .map(|&v| Cell::I64(Self::probability_pct(v))),
                 "rain_volume_1h_mm" => rain_volume_1h_mm
                     .get(row_idx)
                     .and_then(|&v| v.map(Cell::Numeric)),
                 "snow_volume_1h_mm" => snow_volume_1h_mm
                     .get(row_idx)
                     .and_then(|&v| v.map(Cell::Numeric)),
+                "rain_volume_1h_in" => rain_volume_1h_mm
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|v| Cell::Numeric(v / MM_PER_INCH))),
+                "snow_volume_1h_in" => snow_volume_1h_mm
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|v| Cell::Numeric(v / MM_PER_INCH))),
+                "precipitation_type" => precipitation_type
+                    .get(row_idx)
+                    .map(|v| Cell::String(v.to_string())),
                 "weather_condition" => weather_condition
                     .get(row_idx)
                     .map(|v| Cell::String(v.clone())),
+                "weather_condition_code" => weather_condition_code
+                    .get(row_idx)
+                    .and_then(|&v| v.map(Cell::I64)),
                 "weather_description" => weather_description
                     .get(row_idx)
                     .map(|v| Cell::String(v.clone())),
                 "weather_icon_code" => weather_icon_code
                     .get(row_idx)
                     .map(|v| Cell::String(v.clone())),
+                "is_daytime" => weather_icon_code
+                    .get(row_idx)
+                    .and_then(|v| Self::is_daytime(v))
+                    .map(Cell::Bool),
+                "weather_all" => weather_all.get(row_idx).map(|v| Cell::Json(v.clone())),
                 _ => {
                     return Err(format!(
                         "unknown column '{}' for hourly_forecast endpoint",
@@ -1600,6 +4556,7 @@ impl OpenWeatherFdw {
             EndpointData::DailyForecast {
                 latitude,
                 longitude,
+                timezone_offset_seconds,
                 forecast_date,
                 sunrise_time,
                 sunset_time,
@@ -1628,20 +4585,32 @@ impl OpenWeatherFdw {
                 snow_volume_mm,
                 uv_index,
                 weather_condition,
+                weather_condition_code,
                 weather_description,
                 weather_icon_code,
-            } => match tgt_col_name.as_str() {
+                day_summary_text,
+            } => match tgt_col_name {
                 "latitude" => Some(Cell::Numeric(*latitude)),
                 "longitude" => Some(Cell::Numeric(*longitude)),
+                "timezone_offset_seconds" => Some(Cell::I64(*timezone_offset_seconds)),
                 "forecast_date" => forecast_date
                     .get(row_idx)
                     .map(|&v| Cell::Timestamptz(v * 1_000_000)),
+                "forecast_date_local" => forecast_date
+                    .get(row_idx)
+                    .map(|&v| Cell::Timestamp((v + timezone_offset_seconds) * 1_000_000)),
                 "sunrise_time" => sunrise_time
                     .get(row_idx)
                     .map(|&v| Cell::Timestamptz(v * 1_000_000)),
+                "sunrise_time_local" => sunrise_time
+                    .get(row_idx)
+                    .map(|&v| Cell::Timestamp((v + timezone_offset_seconds) * 1_000_000)),
                 "sunset_time" => sunset_time
                     .get(row_idx)
                     .map(|&v| Cell::Timestamptz(v * 1_000_000)),
+                "sunset_time_local" => sunset_time
+                    .get(row_idx)
+                    .map(|&v| Cell::Timestamp((v + timezone_offset_seconds) * 1_000_000)),
                 "moonrise_time" => moonrise_time
                     .get(row_idx)
                     .map(|&v| Cell::Timestamptz(v * 1_000_000)),
@@ -1651,6 +4620,12 @@ impl OpenWeatherFdw {
                 "moon_phase_fraction" => {
                     moon_phase_fraction.get(row_idx).map(|&v| Cell::Numeric(v))
                 }
+                "moon_phase_name" => moon_phase_fraction
+                    .get(row_idx)
+                    .map(|&v| Cell::String(Self::moon_phase_name(v).to_string())),
+                "moon_illumination_pct" => moon_phase_fraction
+                    .get(row_idx)
+                    .map(|&v| Cell::Numeric(Self::moon_illumination_pct(v))),
                 "temperature_day_temp" => {
                     temperature_day_temp.get(row_idx).map(|&v| Cell::Numeric(v))
                 }
@@ -1660,6 +4635,11 @@ impl OpenWeatherFdw {
                 "temperature_max_temp" => {
                     temperature_max_temp.get(row_idx).map(|&v| Cell::Numeric(v))
                 }
+                // Diurnal swing; both ends share the query's unit system
+                "temperature_range_temp" => temperature_max_temp
+                    .get(row_idx)
+                    .zip(temperature_min_temp.get(row_idx))
+                    .map(|(max, min)| Cell::Numeric(max - min)),
                 "temperature_night_temp" => temperature_night_temp
                     .get(row_idx)
                     .map(|&v| Cell::Numeric(v)),
@@ -1681,38 +4661,64 @@ impl OpenWeatherFdw {
                 "apparent_temperature_morning_temp" => apparent_temperature_morning_temp
                     .get(row_idx)
                     .map(|&v| Cell::Numeric(v)),
-                "pressure_hpa" => pressure_hpa.get(row_idx).map(|&v| Cell::Numeric(v as f64)),
-                "humidity_pct" => humidity_pct.get(row_idx).map(|&v| Cell::Numeric(v as f64)),
-                "dew_point_temp" => dew_point_temp.get(row_idx).map(|&v| Cell::Numeric(v)),
+                "pressure_hpa" => pressure_hpa.get(row_idx).and_then(|&v| v.map(Cell::I64)),
+                "pressure_inhg" => pressure_hpa
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|p| Cell::Numeric(p as f64 * INHG_PER_HPA))),
+                "humidity_pct" => humidity_pct.get(row_idx).map(|&v| Cell::I64(v)),
+                "dew_point_temp" => dew_point_temp
+                    .get(row_idx)
+                    .and_then(|&v| v.map(Cell::Numeric)),
                 "wind_speed_m_s" => wind_speed_m_s.get(row_idx).map(|&v| Cell::Numeric(v)),
-                "wind_direction_deg" => wind_direction_deg
+                "wind_speed_kt" => wind_speed_m_s
                     .get(row_idx)
-                    .map(|&v| Cell::Numeric(v as f64)),
+                    .map(|&v| Cell::Numeric(self.wind_speed_knots(v))),
+                "wind_direction_deg" => wind_direction_deg.get(row_idx).map(|&v| Cell::I64(v)),
                 "wind_gust_speed_m_s" => wind_gust_speed_m_s
                     .get(row_idx)
                     .and_then(|&v| v.map(Cell::Numeric)),
-                "cloud_cover_pct" => cloud_cover_pct
-                    .get(row_idx)
-                    .map(|&v| Cell::Numeric(v as f64)),
+                "cloud_cover_pct" => cloud_cover_pct.get(row_idx).map(|&v| Cell::I64(v)),
                 "precipitation_probability" => precipitation_probability
                     .get(row_idx)
                     .map(|&v| Cell::Numeric(v)),
+                "precipitation_probability_pct" => precipitation_probability
+                    .get(row_idx)
+                    .map(|&v| Cell::I64(Self::probability_pct(v))),
                 "rain_volume_mm" => rain_volume_mm
                     .get(row_idx)
                     .and_then(|&v| v.map(Cell::Numeric)),
                 "snow_volume_mm" => snow_volume_mm
                     .get(row_idx)
                     .and_then(|&v| v.map(Cell::Numeric)),
-                "uv_index" => uv_index.get(row_idx).map(|&v| Cell::Numeric(v)),
+                "rain_volume_in" => rain_volume_mm
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|v| Cell::Numeric(v / MM_PER_INCH))),
+                "snow_volume_in" => snow_volume_mm
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|v| Cell::Numeric(v / MM_PER_INCH))),
+                "uv_index" => uv_index.get(row_idx).and_then(|&v| v.map(Cell::Numeric)),
+                "uv_index_risk" => uv_index
+                    .get(row_idx)
+                    .and_then(|&v| v.map(|v| Cell::String(Self::uv_index_risk(v).to_string()))),
                 "weather_condition" => weather_condition
                     .get(row_idx)
                     .map(|v| Cell::String(v.clone())),
+                "weather_condition_code" => weather_condition_code
+                    .get(row_idx)
+                    .and_then(|&v| v.map(Cell::I64)),
                 "weather_description" => weather_description
                     .get(row_idx)
                     .map(|v| Cell::String(v.clone())),
                 "weather_icon_code" => weather_icon_code
                     .get(row_idx)
                     .map(|v| Cell::String(v.clone())),
+                "is_daytime" => weather_icon_code
+                    .get(row_idx)
+                    .and_then(|v| Self::is_daytime(v))
+                    .map(Cell::Bool),
+                "day_summary_text" => day_summary_text
+                    .get(row_idx)
+                    .and_then(|v| v.clone().map(Cell::String)),
                 _ => {
                     return Err(format!(
                         "unknown column '{}' for daily_forecast endpoint",
@@ -1727,20 +4733,31 @@ impl OpenWeatherFdw {
                 alerts,
             } => {
                 let alert = alerts.get(row_idx).ok_or("alert index out of bounds")?;
-                match tgt_col_name.as_str() {
+                match tgt_col_name {
                     "latitude" => Some(Cell::Numeric(*latitude)),
                     "longitude" => Some(Cell::Numeric(*longitude)),
                     "alert_sender_name" => Some(Cell::String(alert.alert_sender_name.clone())),
                     "alert_event_type" => Some(Cell::String(alert.alert_event_type.clone())),
+                    "alert_event_normalized" => {
+                        Some(Cell::String(alert.alert_event_normalized.clone()))
+                    }
                     "alert_start_time" => {
                         Some(Cell::Timestamptz(alert.alert_start_time * 1_000_000))
                     }
                     "alert_end_time" => Some(Cell::Timestamptz(alert.alert_end_time * 1_000_000)),
+                    // NULL rather than a negative length for alerts that end before they start
+                    "alert_duration_seconds" => Some(alert.alert_end_time - alert.alert_start_time)
+                        .filter(|duration| *duration >= 0)
+                        .map(Cell::I64),
                     "alert_description" => Some(Cell::String(alert.alert_description.clone())),
+                    "alert_description_lang" => {
+                        alert.alert_description_lang.clone().map(Cell::String)
+                    }
                     "alert_tags" => {
                         // Convert Vec<String> to comma-separated string
                         Some(Cell::String(alert.alert_tags.join(",")))
                     }
+                    "alert_severity" => alert.alert_severity.clone().map(Cell::String),
                     _ => {
                         return Err(format!(
                             "unknown column '{}' for weather_alerts endpoint",
@@ -1750,89 +4767,143 @@ impl OpenWeatherFdw {
                 }
             }
 
-            EndpointData::HistoricalWeather {
+            EndpointData::OneCallSummary {
                 latitude,
                 longitude,
+                timezone_name,
                 observation_time,
                 temperature_temp,
                 apparent_temperature_temp,
-                pressure_hpa,
                 humidity_pct,
-                dew_point_temp,
-                cloud_cover_pct,
-                visibility_m,
                 wind_speed_m_s,
-                wind_direction_deg,
                 weather_condition,
                 weather_description,
-                weather_icon_code,
-            } => match tgt_col_name.as_str() {
+                next_hour_precip_mm,
+                today_min_temp,
+                today_max_temp,
+                active_alerts_count,
+            } => match tgt_col_name {
                 "latitude" => Some(Cell::Numeric(*latitude)),
                 "longitude" => Some(Cell::Numeric(*longitude)),
+                "timezone_name" => Some(Cell::String(timezone_name.clone())),
                 "observation_time" => Some(Cell::Timestamptz(observation_time * 1_000_000)),
                 "temperature_temp" => Some(Cell::Numeric(*temperature_temp)),
                 "apparent_temperature_temp" => Some(Cell::Numeric(*apparent_temperature_temp)),
-                "pressure_hpa" => Some(Cell::Numeric(*pressure_hpa as f64)),
-                "humidity_pct" => Some(Cell::Numeric(*humidity_pct as f64)),
-                "dew_point_temp" => Some(Cell::Numeric(*dew_point_temp)),
-                "cloud_cover_pct" => Some(Cell::Numeric(*cloud_cover_pct as f64)),
-                "visibility_m" => Some(Cell::Numeric(*visibility_m as f64)),
+                "humidity_pct" => Some(Cell::I64(*humidity_pct)),
                 "wind_speed_m_s" => Some(Cell::Numeric(*wind_speed_m_s)),
-                "wind_direction_deg" => Some(Cell::Numeric(*wind_direction_deg as f64)),
                 "weather_condition" => Some(Cell::String(weather_condition.clone())),
                 "weather_description" => Some(Cell::String(weather_description.clone())),
-                "weather_icon_code" => Some(Cell::String(weather_icon_code.clone())),
+                "next_hour_precip_mm" => next_hour_precip_mm.map(Cell::Numeric),
+                "today_min_temp" => today_min_temp.map(Cell::Numeric),
+                "today_max_temp" => today_max_temp.map(Cell::Numeric),
+                "active_alerts_count" => active_alerts_count.map(Cell::I64),
+                "has_active_alerts" => active_alerts_count.map(|count| Cell::Bool(count > 0)),
                 _ => {
                     return Err(format!(
-                        "unknown column '{}' for historical_weather endpoint",
+                        "unknown column '{}' for fetch_all_onecall endpoint",
                         tgt_col_name
                     ))
                 }
             },
 
-            EndpointData::DailySummary {
+            EndpointData::HistoricalWeather {
                 latitude,
                 longitude,
-                timezone_offset,
-                summary_date,
-                unit_system,
-                temperature_min_temp,
-                temperature_max_temp,
-                temperature_morning_temp,
-                temperature_afternoon_temp,
-                temperature_evening_temp,
-                temperature_night_temp,
-                cloud_cover_afternoon_pct,
-                humidity_afternoon_pct,
-                pressure_afternoon_hpa,
-                precipitation_total_mm,
-                wind_max_speed_m_s,
-                wind_max_direction_deg,
-            } => match tgt_col_name.as_str() {
-                "latitude" => Some(Cell::Numeric(*latitude)),
-                "longitude" => Some(Cell::Numeric(*longitude)),
-                "timezone_offset" => Some(Cell::String(timezone_offset.clone())),
-                "summary_date" => Some(Cell::String(summary_date.clone())),
-                "unit_system" => Some(Cell::String(unit_system.clone())),
-                "temperature_min_temp" => Some(Cell::Numeric(*temperature_min_temp)),
-                "temperature_max_temp" => Some(Cell::Numeric(*temperature_max_temp)),
-                "temperature_morning_temp" => Some(Cell::Numeric(*temperature_morning_temp)),
-                "temperature_afternoon_temp" => Some(Cell::Numeric(*temperature_afternoon_temp)),
-                "temperature_evening_temp" => Some(Cell::Numeric(*temperature_evening_temp)),
-                "temperature_night_temp" => Some(Cell::Numeric(*temperature_night_temp)),
-                "cloud_cover_afternoon_pct" => Some(Cell::Numeric(*cloud_cover_afternoon_pct)),
-                "humidity_afternoon_pct" => Some(Cell::Numeric(*humidity_afternoon_pct)),
-                "pressure_afternoon_hpa" => Some(Cell::Numeric(*pressure_afternoon_hpa)),
-                "precipitation_total_mm" => Some(Cell::Numeric(*precipitation_total_mm)),
-                "wind_max_speed_m_s" => Some(Cell::Numeric(*wind_max_speed_m_s)),
-                "wind_max_direction_deg" => Some(Cell::Numeric(*wind_max_direction_deg)),
-                _ => {
-                    return Err(format!(
-                        "unknown column '{}' for daily_summary endpoint",
-                        tgt_col_name
-                    ))
+                observations,
+            } => {
+                let HistoricalObservation {
+                    observation_time,
+                    temperature_temp,
+                    apparent_temperature_temp,
+                    pressure_hpa,
+                    humidity_pct,
+                    dew_point_temp,
+                    cloud_cover_pct,
+                    visibility_m,
+                    wind_speed_m_s,
+                    wind_direction_deg,
+                    weather_condition,
+                    weather_condition_code,
+                    weather_description,
+                    weather_icon_code,
+                } = observations
+                    .get(row_idx)
+                    .ok_or("observation index out of bounds")?;
+                match tgt_col_name {
+                    "latitude" => Some(Cell::Numeric(*latitude)),
+                    "longitude" => Some(Cell::Numeric(*longitude)),
+                    "observation_time" => Some(Cell::Timestamptz(observation_time * 1_000_000)),
+                    "dt" => Some(Cell::I64(*observation_time)),
+                    "temperature_temp" => Some(Cell::Numeric(*temperature_temp)),
+                    "apparent_temperature_temp" => Some(Cell::Numeric(*apparent_temperature_temp)),
+                    "apparent_temp_delta" => {
+                        Some(Cell::Numeric(apparent_temperature_temp - temperature_temp))
+                    }
+                    "pressure_hpa" => pressure_hpa.map(Cell::I64),
+                    "pressure_inhg" => pressure_hpa.map(|p| Cell::Numeric(p as f64 * INHG_PER_HPA)),
+                    "humidity_pct" => Some(Cell::I64(*humidity_pct)),
+                    "dew_point_temp" => dew_point_temp.map(Cell::Numeric),
+                    "cloud_cover_pct" => Some(Cell::I64(*cloud_cover_pct)),
+                    "visibility_m" => visibility_m.map(Cell::I64),
+                    "visibility_km" => visibility_m.map(|v| Cell::Numeric(v as f64 / 1000.0)),
+                    "visibility_mi" => {
+                        visibility_m.map(|v| Cell::Numeric(v as f64 / METERS_PER_MILE))
+                    }
+                    "wind_speed_m_s" => Some(Cell::Numeric(*wind_speed_m_s)),
+                    "wind_direction_deg" => Some(Cell::I64(*wind_direction_deg)),
+                    "weather_condition" => Some(Cell::String(weather_condition.clone())),
+                    "weather_condition_code" => weather_condition_code.map(Cell::I64),
+                    "weather_description" => Some(Cell::String(weather_description.clone())),
+                    "weather_icon_code" => Some(Cell::String(weather_icon_code.clone())),
+                    "is_daytime" => Self::is_daytime(weather_icon_code).map(Cell::Bool),
+                    _ => {
+                        return Err(format!(
+                            "unknown column '{}' for historical_weather endpoint",
+                            tgt_col_name
+                        ))
+                    }
                 }
-            },
+            }
+
+            EndpointData::DailySummary { summaries } => {
+                let summary = summaries
+                    .get(row_idx)
+                    .ok_or("summary index out of bounds")?;
+                match tgt_col_name {
+                    "latitude" => Some(Cell::Numeric(summary.latitude)),
+                    "longitude" => Some(Cell::Numeric(summary.longitude)),
+                    "timezone_offset" => Some(Cell::String(summary.timezone_offset.clone())),
+                    "summary_date" => Some(Cell::String(summary.summary_date.clone())),
+                    "unit_system" => Some(Cell::String(summary.unit_system.clone())),
+                    "temperature_min_temp" => Some(Cell::Numeric(summary.temperature_min_temp)),
+                    "temperature_max_temp" => Some(Cell::Numeric(summary.temperature_max_temp)),
+                    "temperature_morning_temp" => {
+                        Some(Cell::Numeric(summary.temperature_morning_temp))
+                    }
+                    "temperature_afternoon_temp" => {
+                        Some(Cell::Numeric(summary.temperature_afternoon_temp))
+                    }
+                    "temperature_evening_temp" => {
+                        Some(Cell::Numeric(summary.temperature_evening_temp))
+                    }
+                    "temperature_night_temp" => Some(Cell::Numeric(summary.temperature_night_temp)),
+                    "cloud_cover_afternoon_pct" => {
+                        Some(Cell::Numeric(summary.cloud_cover_afternoon_pct))
+                    }
+                    "humidity_afternoon_pct" => Some(Cell::Numeric(summary.humidity_afternoon_pct)),
+                    "pressure_afternoon_hpa" => Some(Cell::Numeric(summary.pressure_afternoon_hpa)),
+                    "precipitation_total_mm" => Some(Cell::Numeric(summary.precipitation_total_mm)),
+                    "wind_max_speed_m_s" => Some(Cell::Numeric(summary.wind_max_speed_m_s)),
+                    "wind_max_direction_deg" => Some(Cell::Numeric(summary.wind_max_direction_deg)),
+                    "api_message" => summary.api_message.clone().map(Cell::String),
+                    _ => {
+                        return Err(format!(
+                            "unknown column '{}' for daily_summary endpoint",
+                            tgt_col_name
+                        ))
+                    }
+                }
+            }
 
             EndpointData::WeatherOverview {
                 latitude,
@@ -1841,13 +4912,19 @@ impl OpenWeatherFdw {
                 overview_date,
                 unit_system,
                 weather_overview,
-            } => match tgt_col_name.as_str() {
+                mentioned_high_temp,
+                mentioned_low_temp,
+                api_message,
+            } => match tgt_col_name {
                 "latitude" => Some(Cell::Numeric(*latitude)),
                 "longitude" => Some(Cell::Numeric(*longitude)),
                 "timezone_offset" => Some(Cell::String(timezone_offset.clone())),
                 "overview_date" => Some(Cell::String(overview_date.clone())),
                 "unit_system" => Some(Cell::String(unit_system.clone())),
                 "weather_overview" => Some(Cell::String(weather_overview.clone())),
+                "mentioned_high_temp" => mentioned_high_temp.map(Cell::Numeric),
+                "mentioned_low_temp" => mentioned_low_temp.map(Cell::Numeric),
+                "api_message" => api_message.clone().map(Cell::String),
                 _ => {
                     return Err(format!(
                         "unknown column '{}' for weather_overview endpoint",
@@ -1856,179 +4933,1370 @@ impl OpenWeatherFdw {
                 }
             },
 
+            EndpointData::AirPollution {
+                latitude,
+                longitude,
+                observation_time,
+                aqi,
+                co_ug_m3,
+                no_ug_m3,
+                no2_ug_m3,
+                o3_ug_m3,
+                so2_ug_m3,
+                pm2_5_ug_m3,
+                pm10_ug_m3,
+                nh3_ug_m3,
+            } => match tgt_col_name {
+                "latitude" => Some(Cell::Numeric(*latitude)),
+                "longitude" => Some(Cell::Numeric(*longitude)),
+                "observation_time" => Some(Cell::Timestamptz(observation_time * 1_000_000)),
+                "aqi" => Some(Cell::I64(*aqi)),
+                "aqi_label" => Self::aqi_label(*aqi).map(|label| Cell::String(label.to_string())),
+                "co_ug_m3" => co_ug_m3.map(Cell::Numeric),
+                "no_ug_m3" => no_ug_m3.map(Cell::Numeric),
+                "no2_ug_m3" => no2_ug_m3.map(Cell::Numeric),
+                "o3_ug_m3" => o3_ug_m3.map(Cell::Numeric),
+                "so2_ug_m3" => so2_ug_m3.map(Cell::Numeric),
+                "pm2_5_ug_m3" => pm2_5_ug_m3.map(Cell::Numeric),
+                "pm10_ug_m3" => pm10_ug_m3.map(Cell::Numeric),
+                "nh3_ug_m3" => nh3_ug_m3.map(Cell::Numeric),
+                _ => {
+                    return Err(format!(
+                        "unknown column '{}' for air_pollution endpoint",
+                        tgt_col_name
+                    ))
+                }
+            },
+
+            EndpointData::CitiesInBbox { bbox, cities } => {
+                let city = cities.get(row_idx).ok_or("city index out of bounds")?;
+                match tgt_col_name {
+                    "min_lat" => Some(Cell::Numeric(bbox.min_lat)),
+                    "min_lon" => Some(Cell::Numeric(bbox.min_lon)),
+                    "max_lat" => Some(Cell::Numeric(bbox.max_lat)),
+                    "max_lon" => Some(Cell::Numeric(bbox.max_lon)),
+                    "zoom" => Some(Cell::I64(bbox.zoom)),
+                    _ => Self::city_cell(city, tgt_col_name).ok_or(format!(
+                        "unknown column '{}' for cities_in_bbox endpoint",
+                        tgt_col_name
+                    ))?,
+                }
+            }
+
+            EndpointData::CitiesGroup { city_ids, cities } => {
+                let city = cities.get(row_idx).ok_or("city index out of bounds")?;
+                match tgt_col_name {
+                    "city_ids" => Some(Cell::String(city_ids.clone())),
+                    _ => Self::city_cell(city, tgt_col_name).ok_or(format!(
+                        "unknown column '{}' for cities_group endpoint",
+                        tgt_col_name
+                    ))?,
+                }
+            }
+
+            EndpointData::Geocoding {
+                city_name,
+                limit,
+                matches,
+            } => {
+                let geo = matches.get(row_idx).ok_or("match index out of bounds")?;
+                match tgt_col_name {
+                    "city_name" => Some(Cell::String(city_name.clone())),
+                    "limit" => Some(Cell::I64(*limit)),
+                    "location_name" => Some(Cell::String(geo.location_name.clone())),
+                    "latitude" => Some(Cell::Numeric(geo.latitude)),
+                    "longitude" => Some(Cell::Numeric(geo.longitude)),
+                    "country_code" => Some(Cell::String(geo.country_code.clone())),
+                    "state_name" => geo.state_name.clone().map(Cell::String),
+                    _ => {
+                        return Err(format!(
+                            "unknown column '{}' for geocoding endpoint",
+                            tgt_col_name
+                        ))
+                    }
+                }
+            }
+
+            EndpointData::ApiUsageLog { entries } => {
+                let entry = entries
+                    .get(row_idx)
+                    .ok_or("usage log index out of bounds")?;
+                match tgt_col_name {
+                    "endpoint" => Some(Cell::String(entry.endpoint.clone())),
+                    "latitude" => entry.latitude.map(Cell::Numeric),
+                    "longitude" => entry.longitude.map(Cell::Numeric),
+                    "logged_count" => Some(Cell::I64(entry.logged_count)),
+                    "last_logged_time" => {
+                        Some(Cell::Timestamptz(entry.last_logged_time * 1_000_000))
+                    }
+                    _ => {
+                        return Err(format!(
+                            "unknown column '{}' for api_usage_log endpoint",
+                            tgt_col_name
+                        ))
+                    }
+                }
+            }
+
+            // Every table can show dry_run rows: the diagnostic columns plus echoed request
+            // parameters (so PostgreSQL's qual recheck keeps the row), NULL elsewhere
+            EndpointData::DryRun { requests } => {
+                let request = requests.get(row_idx).ok_or("dry run index out of bounds")?;
+                match tgt_col_name {
+                    "request_url" => Some(Cell::String(request.request_url.clone())),
+                    "http_method" => Some(Cell::String(request.http_method.to_string())),
+                    "request_headers" => Some(Cell::Json(request.request_headers.clone())),
+                    "latitude" => Some(Cell::Numeric(self.latitude)),
+                    "longitude" => Some(Cell::Numeric(self.longitude)),
+                    "observation_time" => request.dt.map(|dt| Cell::Timestamptz(dt * 1_000_000)),
+                    "dt" => request.dt.map(Cell::I64),
+                    "summary_date" | "overview_date" => request.date.clone().map(Cell::String),
+                    "city_name" => request.city_name.clone().map(Cell::String),
+                    "city_id" => request.city_id.map(Cell::I64),
+                    "city_ids" => request.city_ids.clone().map(Cell::String),
+                    "location" => request.location.clone().map(Cell::String),
+                    "units" => Some(Cell::String(request.units.clone())),
+                    "lang" => Some(Cell::String(request.lang.clone())),
+                    "limit" => request.limit.map(Cell::I64),
+                    "timezone_offset" => request.timezone_offset.clone().map(Cell::String),
+                    "min_lat" => request.bbox.map(|bbox| Cell::Numeric(bbox.min_lat)),
+                    "min_lon" => request.bbox.map(|bbox| Cell::Numeric(bbox.min_lon)),
+                    "max_lat" => request.bbox.map(|bbox| Cell::Numeric(bbox.max_lat)),
+                    "max_lon" => request.bbox.map(|bbox| Cell::Numeric(bbox.max_lon)),
+                    "zoom" => request.bbox.map(|bbox| Cell::I64(bbox.zoom)),
+                    _ => None,
+                }
+            }
+
+            EndpointData::RequestMetadata { metadata } => {
+                let metadata = metadata.as_ref().ok_or("request metadata not loaded")?;
+                match tgt_col_name {
+                    "endpoint" => Some(Cell::String(metadata.endpoint.clone())),
+                    "request_url" => Some(Cell::String(metadata.request_url.clone())),
+                    "status_code" => Some(Cell::I64(metadata.status_code)),
+                    "response_bytes" => Some(Cell::I64(metadata.response_bytes)),
+                    "request_count" => Some(Cell::I64(metadata.request_count)),
+                    "latency_ms" => metadata.latency_ms.map(Cell::I64),
+                    "recommended_refresh_seconds" => {
+                        EndpointType::from_object_name(&metadata.endpoint)
+                            .ok()
+                            .and_then(|endpoint_type| endpoint_type.recommended_refresh_seconds())
+                            .map(Cell::I64)
+                    }
+                    _ => {
+                        return Err(format!(
+                            "unknown column '{}' for request_metadata endpoint",
+                            tgt_col_name
+                        ))
+                    }
+                }
+            }
+
             EndpointData::None => {
                 return Err("no data loaded - fetch_source_data not called".to_owned());
             }
         };
 
+        // Round numeric weather values when requested; coordinates are left intact so
+        // PostgreSQL's re-check of the latitude/longitude quals still matches
+        let cell = match (cell, self.round_decimals) {
+            (Some(Cell::Numeric(v)), Some(decimals))
+                if !matches!(tgt_col_name, "latitude" | "longitude") =>
+            {
+                let factor = 10f64.powi(decimals as i32);
+                Some(Cell::Numeric((v * factor).round() / factor))
+            }
+            (cell, _) => cell,
+        };
+
         Ok(cell)
     }
 
-    /// Fetch data from OpenWeather API based on endpoint type
-    fn fetch_source_data(&mut self) -> FdwResult {
-        let endpoint_type = self
-            .endpoint_type
-            .ok_or("endpoint type not set - call begin_scan first")?;
+    /// Map a per-city weather column to a cell; None when the column is not a city column
+    fn city_cell(city: &CityWeatherRow, tgt_col_name: &str) -> Option<Option<Cell>> {
+        let cell = match tgt_col_name {
+            "city_id" => Some(Cell::I64(city.city_id)),
+            "city_name" => Some(Cell::String(city.city_name.clone())),
+            "latitude" => Some(Cell::Numeric(city.latitude)),
+            "longitude" => Some(Cell::Numeric(city.longitude)),
+            "observation_time" => Some(Cell::Timestamptz(city.observation_time * 1_000_000)),
+            "temperature_temp" => Some(Cell::Numeric(city.temperature_temp)),
+            "apparent_temperature_temp" => city.apparent_temperature_temp.map(Cell::Numeric),
+            "pressure_hpa" => city.pressure_hpa.map(Cell::I64),
+            "humidity_pct" => city.humidity_pct.map(Cell::I64),
+            "cloud_cover_pct" => city.cloud_cover_pct.map(Cell::I64),
+            "wind_speed_m_s" => city.wind_speed_m_s.map(Cell::Numeric),
+            "wind_direction_deg" => city.wind_direction_deg.map(Cell::I64),
+            "weather_condition" => Some(Cell::String(city.weather_condition.clone())),
+            "weather_condition_code" => city.weather_condition_code.map(Cell::I64),
+            "weather_description" => Some(Cell::String(city.weather_description.clone())),
+            "weather_icon_code" => Some(Cell::String(city.weather_icon_code.clone())),
+            "is_daytime" => Self::is_daytime(&city.weather_icon_code).map(Cell::Bool),
+            _ => return None,
+        };
+        Some(cell)
+    }
 
-        // Log request details
-        utils::report_info(&format!(
-            "Fetching OpenWeather data for {:?} at latitude={}, longitude={}",
-            endpoint_type, self.latitude, self.longitude
-        ));
+    /// Convert numeric values for columns declared as double precision or real
+    /// (see the numeric_type import option)
+    fn cast_numeric(cell: Option<Cell>, type_oid: TypeOid) -> Option<Cell> {
+        match (cell, type_oid) {
+            (Some(Cell::Numeric(v)), TypeOid::F64) => Some(Cell::F64(v)),
+            (Some(Cell::Numeric(v)), TypeOid::F32) => Some(Cell::F32(v as f32)),
+            (cell, _) => cell,
+        }
+    }
+
+    /// Check if current_weather is served by the legacy /weather endpoint
+    /// (city_id queries, fast_current, or any query against API 2.5)
+    fn uses_legacy_weather(&self, endpoint_type: EndpointType) -> bool {
+        endpoint_type == EndpointType::CurrentWeather
+            && (self.city_id.is_some() || self.fast_current || self.api_version == "2.5")
+    }
+
+    /// Check if this scan is served by /onecall
+    fn uses_onecall(&self, endpoint_type: EndpointType) -> bool {
+        endpoint_type.calls_onecall() && !self.uses_legacy_weather(endpoint_type)
+    }
+
+    /// Coordinates sent to the API, rounded to coord_precision decimals when set
+    ///
+    /// Output columns keep the queried coordinates so PostgreSQL's re-check of the
+    /// latitude/longitude quals still matches.
+    fn request_location(&self) -> (f64, f64) {
+        match self.coord_precision {
+            Some(decimals) => {
+                let factor = 10f64.powi(decimals as i32);
+                (
+                    (self.latitude * factor).round() / factor,
+                    (self.longitude * factor).round() / factor,
+                )
+            }
+            None => (self.latitude, self.longitude),
+        }
+    }
+
+    /// Milliseconds to wait so requests are at least `interval_ms` apart. The host
+    /// clock only has whole-second resolution, so only fully elapsed seconds count
+    /// and the wait errs on the long side
+    fn request_delay_ms(interval_ms: u64, last_request_at: Option<i64>, now: i64) -> u64 {
+        match last_request_at {
+            Some(last) => {
+                let elapsed_ms = (now - last - 1).max(0) as u64 * 1000;
+                interval_ms.saturating_sub(elapsed_ms)
+            }
+            None => 0,
+        }
+    }
+
+    /// Pseudo-random delay in 0..=max_ms. There is no entropy source in the sandbox,
+    /// so the seed mixes the clock with the request URL to spread concurrent queries
+    fn next_jitter_ms(&mut self, max_ms: u64, url: &str) -> u64 {
+        if self.jitter_state == 0 {
+            let seed = url.bytes().fold(time::epoch_secs() as u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+            self.jitter_state = seed | 1;
+        }
+        let mut x = self.jitter_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.jitter_state = x;
+        x % (max_ms + 1)
+    }
+
+    /// Sleep before an outbound request to honour min_request_interval_ms and
+    /// request_jitter_ms (best effort, tracked per WASM instance)
+    fn throttle(&mut self, url: &str) {
+        let mut wait_ms = self.min_request_interval_ms.map_or(0, |interval| {
+            Self::request_delay_ms(interval, self.last_request_at, time::epoch_secs())
+        });
+        if let Some(jitter) = self.request_jitter_ms.filter(|&ms| ms > 0) {
+            wait_ms += self.next_jitter_ms(jitter, url);
+        }
+        if wait_ms > 0 {
+            time::sleep(wait_ms);
+        }
+        self.last_request_at = Some(time::epoch_secs());
+    }
+
+    /// Cache key for the current /onecall request parameters
+    fn onecall_cache_key(&self) -> Result<OnecallCacheKey, FdwError> {
+        let mut hasher = DefaultHasher::new();
+        self.api_key.hash(&mut hasher);
+        Ok((
+            Self::redact_url(&self.create_request()?.url),
+            hasher.finish(),
+        ))
+    }
+
+    /// Stats metadata entry caching a /onecall response under its request key
+    fn onecall_cache_entry(
+        key: &OnecallCacheKey,
+        fetched_at: i64,
+        resp_json: &JsonValue,
+    ) -> JsonValue {
+        serde_json::json!({
+            "url": key.0,
+            "key_hash": key.1,
+            "fetched_at": fetched_at,
+            "response": resp_json,
+        })
+    }
+
+    /// Cached /onecall response for this request key, unless older than ONECALL_CACHE_TTL_SECS
+    fn cached_onecall_response(
+        entry: &JsonValue,
+        key: &OnecallCacheKey,
+        now: i64,
+    ) -> Option<JsonValue> {
+        let matches = entry.get("url").and_then(|v| v.as_str()) == Some(key.0.as_str())
+            && entry.get("key_hash").and_then(|v| v.as_u64()) == Some(key.1);
+        let age = now - entry.get("fetched_at").and_then(|v| v.as_i64())?;
+        (matches && (0..ONECALL_CACHE_TTL_SECS).contains(&age))
+            .then(|| entry.get("response").cloned())
+            .flatten()
+    }
+
+    /// Share a /onecall response with later scans, which run in fresh instances
+    fn store_onecall_response(&self, resp_json: &JsonValue) -> FdwResult {
+        let entry =
+            Self::onecall_cache_entry(&self.onecall_cache_key()?, time::epoch_secs(), resp_json);
+        stats::set_metadata(ONECALL_CACHE_METADATA_KEY, &Some(entry.to_string()));
+        Ok(())
+    }
+
+    /// Execute the request for the current parameters and parse the JSON body,
+    /// reusing a recent cached /onecall response for the same request
+    fn fetch_json(&mut self, endpoint_type: EndpointType) -> Result<JsonValue, FdwError> {
+        if self.uses_onecall(endpoint_type) {
+            let key = self.onecall_cache_key()?;
+            let cached = stats::get_metadata(ONECALL_CACHE_METADATA_KEY)
+                .and_then(|raw| serde_json::from_str::<JsonValue>(&raw).ok())
+                .and_then(|entry| Self::cached_onecall_response(&entry, &key, time::epoch_secs()));
+            if let Some(json) = cached {
+                report_info("Reusing cached /onecall response");
+                return Ok(json);
+            }
+        }
 
         // Create and execute HTTP request
+        // NOTE: the host http interface takes no timeout parameter, so request_timeout_ms
+        // only shapes the error message when the host reports a timed-out request
         let req = self.create_request()?;
-        let resp = http::get(&req)?;
-
-        // Check for HTTP errors
-        http::error_for_status(&resp).map_err(|err| format!("{}: {}", err, resp.body))?;
+        self.throttle(&req.url);
+        // The http binding reports no timings and the host clock only has whole-second
+        // resolution, so latency is a coarse wall-clock measurement
+        let started_at = time::epoch_secs();
+        let resp = Self::send_request(&req).map_err(|err| match self.request_timeout_ms {
+            Some(timeout) if err.to_lowercase().contains("timed out") => format!(
+                "OpenWeather request timed out (request_timeout_ms = {}): {}",
+                timeout, err
+            ),
+            _ => err,
+        })?;
+        let latency_ms = (time::epoch_secs() - started_at) * 1000;
+
+        // Record request details for the request_metadata table. stats::Metric has no
+        // request counter, so the running count of real HTTP calls (cache hits return
+        // earlier) is kept in the same persisted metadata
+        let request_count = self
+            .stored_request_metadata()
+            .map_or(0, |previous| previous.request_count);
+        let metadata = RequestMetadata {
+            endpoint: endpoint_type.object_name().to_string(),
+            request_url: Self::redact_url(&req.url),
+            status_code: resp.status_code as i64,
+            response_bytes: resp.body.len() as i64,
+            request_count: request_count + 1,
+            latency_ms: Some(latency_ms),
+        };
+        stats::set_metadata(FDW_NAME, &Some(metadata.to_json().to_string()));
+        self.last_request = Some(metadata);
+
+        Self::check_content_encoding(&resp.headers)?;
+
+        // Common failures (401, 403, 404, 429) get actionable messages; other HTTP
+        // errors prefer OpenWeather's own error envelope when present
+        let api_error = serde_json::from_str(&resp.body)
+            .ok()
+            .and_then(|json| Self::api_error(&json));
+        let one_call_3 = self.uses_onecall(endpoint_type) && self.api_version == "3.0";
+        if let Some(err) = Self::status_error(
+            resp.status_code,
+            endpoint_type,
+            one_call_3,
+            api_error.as_deref(),
+        ) {
+            return Err(err);
+        }
+        http::error_for_status(&resp)
+            .map_err(|err| api_error.unwrap_or_else(|| format!("{}: {}", err, resp.body)))?;
 
-        utils::report_info(&format!(
+        report_info(&format!(
             "API Response: {} bytes, status {}",
             resp.body.len(),
             resp.status_code
         ));
+        self.inc_stats(stats::Metric::BytesIn, resp.body.len() as i64);
 
-        // Parse JSON response
+        // Parse JSON response; some errors arrive as HTTP 200 with an error envelope
         let resp_json: JsonValue =
             serde_json::from_str(&resp.body).map_err(|e| format!("JSON parse error: {}", e))?;
-
-        // Parse response based on endpoint type
-        match endpoint_type {
-            EndpointType::CurrentWeather => self.parse_current_weather(&resp_json)?,
-            EndpointType::MinutelyForecast => self.parse_minutely_forecast(&resp_json)?,
-            EndpointType::HourlyForecast => self.parse_hourly_forecast(&resp_json)?,
-            EndpointType::DailyForecast => self.parse_daily_forecast(&resp_json)?,
-            EndpointType::WeatherAlerts => self.parse_weather_alerts(&resp_json)?,
-            EndpointType::HistoricalWeather => self.parse_historical_weather(&resp_json)?,
-            EndpointType::DailySummary => self.parse_daily_summary(&resp_json)?,
-            EndpointType::WeatherOverview => self.parse_weather_overview(&resp_json)?,
+        if let Some(err) = Self::api_error(&resp_json) {
+            return Err(err);
+        }
+        self.check_location_tolerance(endpoint_type, &resp_json)?;
+        if let Some(message) = Self::api_message(&resp_json) {
+            report_info(&format!(
+                "OpenWeather API note for {}: {}",
+                endpoint_type.object_name(),
+                message
+            ));
         }
+        Ok(resp_json)
+    }
 
-        // Track stats
-        let row_count = self.data.row_count();
-        stats::inc_stats(FDW_NAME, stats::Metric::BytesIn, resp.body.len() as i64);
-        stats::inc_stats(FDW_NAME, stats::Metric::RowsIn, row_count as i64);
+    /// Make sure a compressed response body was decoded before it reached us
+    ///
+    /// The host interface hands the body over as a string, so compressed bytes cannot be
+    /// decoded here. Whether gzip is decompressed depends on the host HTTP client, which
+    /// drops the content-encoding header when it does; a remaining non-identity encoding
+    /// means the body is still compressed.
+    fn check_content_encoding(headers: &[(String, String)]) -> Result<(), FdwError> {
+        let encoding = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-encoding"))
+            .map(|(_, value)| value.trim());
+        match encoding {
+            Some(enc) if !enc.is_empty() && !enc.eq_ignore_ascii_case("identity") => Err(format!(
+                "OpenWeather response is '{}'-encoded but was not decompressed by the host (unset the request_gzip server option)",
+                enc
+            )),
+            _ => Ok(()),
+        }
+    }
 
-        utils::report_info(&format!("Parsed {} rows", row_count));
+    /// Detect OpenWeather's `{"cod": ..., "message": ...}` error envelope
+    ///
+    /// `cod` is a string or a number depending on the API; 200 means success.
+    fn api_error(resp_json: &JsonValue) -> Option<FdwError> {
+        let cod = match resp_json.get("cod")? {
+            JsonValue::String(cod) => cod.clone(),
+            JsonValue::Number(cod) => cod.to_string(),
+            _ => return None,
+        };
+        if cod == "200" {
+            return None;
+        }
+        let message = resp_json
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown error");
+        Some(format!("OpenWeather API error {}: {}", cod, message))
+    }
 
-        // Reset row iterator
-        self.current_row = 0;
+    /// Location a response reports: top-level lat/lon, or coord.lat/lon (legacy APIs)
+    fn response_location(resp_json: &JsonValue) -> Option<(f64, f64)> {
+        let source = match resp_json.get("coord") {
+            Some(coord) if resp_json.get("lat").is_none() => coord,
+            _ => resp_json,
+        };
+        Some((source.get("lat")?.as_f64()?, source.get("lon")?.as_f64()?))
+    }
 
+    /// Reject a response whose location is further than location_tolerance_deg from
+    /// the requested coordinates (OpenWeather snaps to nearby grid points)
+    fn check_location_tolerance(
+        &self,
+        endpoint_type: EndpointType,
+        resp_json: &JsonValue,
+    ) -> FdwResult {
+        let Some(tolerance) = self.location_tolerance_deg else {
+            return Ok(());
+        };
+        if !endpoint_type.requires_location() || self.city_id.is_some() {
+            return Ok(());
+        }
+        let Some((latitude, longitude)) = Self::response_location(resp_json) else {
+            return Ok(());
+        };
+        let (requested_lat, requested_lon) = self.request_location();
+        let lon_delta = (longitude - requested_lon).abs() % 360.0;
+        let deviation = (latitude - requested_lat)
+            .abs()
+            .max(lon_delta.min(360.0 - lon_delta));
+        if deviation > tolerance {
+            return Err(format!(
+                "OpenWeather returned data for latitude={}, longitude={}, {:.4}° from the \
+                 requested latitude={}, longitude={} (location_tolerance_deg is {})",
+                latitude, longitude, deviation, requested_lat, requested_lon, tolerance
+            ));
+        }
         Ok(())
     }
-}
 
-struct OpenWeatherFdwImpl;
+    /// Non-empty top-level 'message' on a successful response (e.g. degraded data notes)
+    fn api_message(resp_json: &JsonValue) -> Option<String> {
+        resp_json
+            .get("message")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|message| !message.is_empty())
+            .map(str::to_string)
+    }
 
-impl Guest for OpenWeatherFdwImpl {
-    fn host_version_requirement() -> String {
-        // Supabase Wrappers version requirement
-        // Compatible with both local (0.1.5) and production (0.2.x+)
-        // This must match WIT declarations in wit/world.wit
-        "^0.1.0".to_string()
+    /// Actionable messages for rejected credentials (401), plans lacking an API (403),
+    /// wrong paths (404) and rate limiting (429). `one_call_3` marks a /onecall request
+    /// on API 3.0, whose 403 usually means the separate subscription is missing
+    fn status_error(
+        status_code: u16,
+        endpoint_type: EndpointType,
+        one_call_3: bool,
+        api_error: Option<&str>,
+    ) -> Option<FdwError> {
+        match status_code {
+            401 => Some(
+                "OpenWeather rejected the API key (401). Check the api_key server option \
+                 (or api_key_id, or a table-level key override). New keys can take a few \
+                 hours to activate."
+                    .to_string(),
+            ),
+            403 => Some(format!(
+                "OpenWeather denied access to {} (403). The API key is valid but its \
+                 subscription plan does not include {}{}",
+                endpoint_type.object_name(),
+                endpoint_type.api_path(),
+                if one_call_3 {
+                    "; One Call API 3.0 needs a separate 'One Call by Call' subscription."
+                } else {
+                    "."
+                }
+            )),
+            // A 404 for an unknown city ID is a data problem, reported as the API words it
+            404 if api_error.is_some_and(|err| err.contains("city not found")) => None,
+            404 => Some(format!(
+                "OpenWeather endpoint not found (404) for {}; check the api_url and \
+                 api_version server options",
+                endpoint_type.object_name()
+            )),
+            429 => Some(
+                "OpenWeather rate limit exceeded (429); space out requests with the \
+                 min_request_interval_ms or request_jitter_ms server options, or query \
+                 fewer locations"
+                    .to_string(),
+            ),
+            _ => None,
+        }
     }
 
-    fn init(ctx: &Context) -> FdwResult {
-        OpenWeatherFdw::init();
+    /// Check if any 'weather' conditions array in the response has an empty description
+    fn has_empty_description(json: &JsonValue) -> bool {
+        match json {
+            JsonValue::Object(map) => map.iter().any(|(key, value)| match (key.as_str(), value) {
+                ("weather", JsonValue::Array(conditions)) => conditions.iter().any(|c| {
+                    c.get("description")
+                        .and_then(|v| v.as_str())
+                        .is_some_and(|d| d.trim().is_empty())
+                }),
+                _ => Self::has_empty_description(value),
+            }),
+            JsonValue::Array(items) => items.iter().any(Self::has_empty_description),
+            _ => false,
+        }
+    }
 
-        // Extract server options
-        let opts = ctx.get_options(&OptionsType::Server);
-        let instance = OpenWeatherFdw::this_mut();
+    /// Fill empty 'weather' descriptions from a fallback response. Forecast entries are
+    /// paired by 'dt' and conditions by 'id'; nothing outside 'weather' arrays is touched
+    fn merge_descriptions(primary: &mut JsonValue, fallback: &JsonValue) {
+        match (primary, fallback) {
+            (JsonValue::Object(map), JsonValue::Object(fallback_map)) => {
+                for (key, value) in map.iter_mut() {
+                    let Some(fallback_value) = fallback_map.get(key) else {
+                        continue;
+                    };
+                    match (key.as_str(), value) {
+                        ("weather", JsonValue::Array(conditions)) => {
+                            Self::merge_condition_descriptions(conditions, fallback_value)
+                        }
+                        (_, value) => Self::merge_descriptions(value, fallback_value),
+                    }
+                }
+            }
+            (JsonValue::Array(items), JsonValue::Array(fallback_items)) => {
+                for item in items.iter_mut() {
+                    let Some(dt) = item.get("dt").and_then(|v| v.as_i64()) else {
+                        continue;
+                    };
+                    let fallback_item = fallback_items
+                        .iter()
+                        .find(|f| f.get("dt").and_then(|v| v.as_i64()) == Some(dt));
+                    if let Some(fallback_item) = fallback_item {
+                        Self::merge_descriptions(item, fallback_item);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
 
-        // Get base URL (default to OpenWeather API v3.0)
-        instance.base_url = match opts.get("api_url") {
-            Some(url) => url.clone(),
-            None => "https://api.openweathermap.org/data/3.0".to_string(),
+    /// Fill empty condition descriptions from the fallback condition with the same 'id'
+    fn merge_condition_descriptions(conditions: &mut [JsonValue], fallback: &JsonValue) {
+        let Some(fallback_conditions) = fallback.as_array() else {
+            return;
         };
+        for condition in conditions {
+            let empty = condition
+                .get("description")
+                .and_then(|v| v.as_str())
+                .is_some_and(|d| d.trim().is_empty());
+            if !empty {
+                continue;
+            }
+            let description = fallback_conditions
+                .iter()
+                .find(|f| f.get("id").is_some() && f.get("id") == condition.get("id"))
+                .and_then(|f| f.get("description"))
+                .filter(|d| d.is_string())
+                .cloned();
+            if let (Some(description), Some(map)) = (description, condition.as_object_mut()) {
+                map.insert("description".to_string(), description);
+            }
+        }
+    }
 
-        // Get API key using Vault (recommended) or plain text (deprecated)
-        instance.api_key = if let Some(vault_id) = opts.get("api_key_id") {
-            // Vault reference - secure method (RECOMMENDED)
-            utils::get_vault_secret(&vault_id)
-                .ok_or("Failed to retrieve API key from Vault. Ensure the secret exists and is accessible.")?
-        } else if let Some(plain_key) = opts.get("api_key") {
-            // Plain text - deprecated but supported for backward compatibility
-            utils::report_warning(
-                "Using plain text 'api_key' is deprecated for security reasons. \
-                 Please migrate to 'api_key_id' with Vault. \
-                 See: https://supabase.com/docs/guides/database/vault"
-            );
-            plain_key.clone()
-        } else {
-            return Err("Either 'api_key' or 'api_key_id' must be provided in server options".to_string());
+    /// Refetch in description_fallback_lang and merge when a weather description came back empty
+    fn apply_description_fallback(
+        &mut self,
+        endpoint_type: EndpointType,
+        resp_json: &mut JsonValue,
+    ) -> FdwResult {
+        let fallback_lang = match &self.description_fallback_lang {
+            Some(lang) if *lang != self.lang && Self::has_empty_description(resp_json) => {
+                lang.clone()
+            }
+            _ => return Ok(()),
         };
 
-        // Set up HTTP headers
-        instance.headers.push((
-            "user-agent".to_owned(),
-            "Supabase Wrappers OpenWeather FDW".to_string(),
+        report_info(&format!(
+            "Empty weather description for lang '{}', refetching in '{}'",
+            self.lang, fallback_lang
         ));
-        instance
-            .headers
-            .push(("accept".to_owned(), "application/json".to_string()));
+        let primary_lang = std::mem::replace(&mut self.lang, fallback_lang);
+        let fallback_json = self.fetch_json(endpoint_type);
+        self.lang = primary_lang;
 
-        utils::report_info(&format!(
-            "OpenWeather FDW initialized with URL: {}",
-            instance.base_url
-        ));
-        stats::inc_stats(FDW_NAME, stats::Metric::CreateTimes, 1);
+        Self::merge_descriptions(resp_json, &fallback_json?);
         Ok(())
     }
 
-    fn begin_scan(ctx: &Context) -> FdwResult {
-        let instance = OpenWeatherFdw::this_mut();
+    /// Build the requests this scan would send (one per summary date or historical
+    /// timestamp) and store them as dry_run rows, without calling the API
+    fn load_dry_run(&mut self, endpoint_type: EndpointType) -> FdwResult {
+        if endpoint_type.requires_location() && self.geocode_city_name {
+            report_info(
+                "dry_run: city_name would be geocoded first; coordinates below are placeholders",
+            );
+        }
+        // as_of serves current_weather from timemachine, as fetch_current_as_of does
+        let (request_type, dts): (EndpointType, Vec<Option<i64>>) =
+            match (endpoint_type, self.as_of) {
+                (EndpointType::CurrentWeather, Some(as_of)) => {
+                    (EndpointType::HistoricalWeather, vec![Some(as_of)])
+                }
+                (EndpointType::HistoricalWeather, _) => (
+                    endpoint_type,
+                    self.historical_dts.iter().copied().map(Some).collect(),
+                ),
+                _ => (endpoint_type, vec![self.dt]),
+            };
+        let dates: Vec<Option<String>> = match endpoint_type {
+            EndpointType::DailySummary => self.summary_dates.iter().cloned().map(Some).collect(),
+            _ => vec![self.date.clone()],
+        };
 
-        // Get table options
-        let opts = ctx.get_options(&OptionsType::Table);
+        let saved_type = self.endpoint_type.replace(request_type);
+        let requests = self.dry_run_requests(&dts, &dates);
+        self.endpoint_type = saved_type;
+        self.data = EndpointData::DryRun {
+            requests: requests?,
+        };
+        self.current_row = 0;
+        Ok(())
+    }
 
-        // Parse endpoint type from 'object' option
-        let object_name = opts.get("object").ok_or("'object' option is required")?;
+    /// Build one dry_run request per timestamp and date combination
+    ///
+    /// Values of headers other than the built-in ones are redacted like the API key,
+    /// since extra_headers may carry proxy tokens.
+    fn dry_run_requests(
+        &mut self,
+        dts: &[Option<i64>],
+        dates: &[Option<String>],
+    ) -> Result<Vec<DryRunRequest>, FdwError> {
+        let mut requests = Vec::new();
+        for dt in dts {
+            for date in dates {
+                self.dt = *dt;
+                self.date = date.clone();
+                let req = self.create_request()?;
+                let request_url = Self::redact_url(&req.url);
+                let http_method = Self::method_name(&req.method);
+                report_info(&format!(
+                    "dry_run: would send {} {}",
+                    http_method, request_url
+                ));
+                let headers: serde_json::Map<String, JsonValue> = req
+                    .headers
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = if BUILTIN_HEADERS.contains(&name.as_str()) {
+                            value.clone()
+                        } else {
+                            "REDACTED".to_string()
+                        };
+                        (name.clone(), JsonValue::String(value))
+                    })
+                    .collect();
+                requests.push(DryRunRequest {
+                    request_url,
+                    http_method,
+                    request_headers: JsonValue::Object(headers).to_string(),
+                    dt: *dt,
+                    date: date.clone(),
+                    city_name: self.city_name.clone(),
+                    city_id: self.city_id,
+                    city_ids: (!self.group_city_ids_text.is_empty())
+                        .then(|| self.group_city_ids_text.clone()),
+                    location: self.location.clone(),
+                    units: self.units.clone(),
+                    lang: self.lang.clone(),
+                    limit: (self.endpoint_type == Some(EndpointType::Geocoding))
+                        .then_some(self.geocoding_limit),
+                    timezone_offset: self.timezone_offset.clone(),
+                    bbox: self.bbox,
+                });
+            }
+        }
+        Ok(requests)
+    }
 
-        let endpoint_type = EndpointType::from_object_name(&object_name)?;
-        instance.endpoint_type = Some(endpoint_type);
+    /// HTTP method name as sent on the wire
+    fn method_name(method: &http::Method) -> &'static str {
+        match method {
+            http::Method::Get => "GET",
+            http::Method::Post => "POST",
+            http::Method::Put => "PUT",
+            http::Method::Patch => "PATCH",
+            http::Method::Delete => "DELETE",
+        }
+    }
 
-        // Extract WHERE clause parameters
-        let quals = ctx.get_quals();
+    /// Geocode the city_name qual of a weather table to its best-matching coordinates
+    fn resolve_city_name(&mut self) -> FdwResult {
+        let city_name = self.city_name.clone().unwrap_or_default();
+        let endpoint_type = self.endpoint_type;
+        self.endpoint_type = Some(EndpointType::Geocoding);
+        self.geocoding_limit = 1;
+        let resp_json = self.fetch_json(EndpointType::Geocoding);
+        self.endpoint_type = endpoint_type;
 
-        // Extract and validate location (required for all endpoints)
-        let (latitude, longitude) = OpenWeatherFdw::extract_and_validate_location(&quals)?;
-        instance.latitude = latitude;
-        instance.longitude = longitude;
+        let (latitude, longitude) = Self::first_geocoding_match(&resp_json?).ok_or_else(|| {
+            format!(
+                "city_name '{}' did not match any location. \
+                 Try adding a country code (e.g. 'Berlin,DE') or query by latitude/longitude",
+                city_name
+            )
+        })?;
+        report_info(&format!(
+            "Resolved city_name '{}' to latitude={}, longitude={}",
+            city_name, latitude, longitude
+        ));
+        self.latitude = latitude;
+        self.longitude = longitude;
+        Ok(())
+    }
 
-        // Extract optional parameters with defaults
-        instance.units = OpenWeatherFdw::extract_qual_string(&quals, "units")
-            .unwrap_or_else(|| "metric".to_string());
-        instance.lang =
-            OpenWeatherFdw::extract_qual_string(&quals, "lang").unwrap_or_else(|| "en".to_string());
+    /// Fetch current_weather as of a past time from the timemachine endpoint, mapping
+    /// the observation onto the usual current_weather row
+    fn fetch_current_as_of(&mut self, as_of: i64) -> FdwResult {
+        let endpoint_type = self.endpoint_type;
+        self.endpoint_type = Some(EndpointType::HistoricalWeather);
+        self.dt = Some(as_of);
+        let resp_json = self
+            .fetch_json(EndpointType::HistoricalWeather)
+            .and_then(|mut json| {
+                self.check_response_shape(EndpointType::HistoricalWeather, &json)?;
+                self.apply_description_fallback(EndpointType::HistoricalWeather, &mut json)?;
+                Ok(json)
+            });
+        self.endpoint_type = endpoint_type;
+        let resp_json = resp_json?;
 
-        // Extract endpoint-specific parameters
+        // timemachine carries the observation in data[0] (API 2.5: 'current')
+        let observation = resp_json
+            .get("data")
+            .and_then(|v| v.as_array())
+            .and_then(|data| data.first())
+            .or_else(|| resp_json.get("current"))
+            .ok_or("missing 'data' array in timemachine response")?;
+        let mut current_json = serde_json::json!({ "current": observation });
+        for key in ["timezone", "timezone_offset"] {
+            if let Some(value) = resp_json.get(key) {
+                current_json[key] = value.clone();
+            }
+        }
+        self.parse_current_weather(&current_json)?;
+
+        // A past observation has no alert list, so the count is unknown rather than zero
+        if let EndpointData::CurrentWeather {
+            active_alerts_count,
+            ..
+        } = &mut self.data
+        {
+            *active_alerts_count = None;
+        }
+        Ok(())
+    }
+
+    /// Coordinates of the first match in a /geo/1.0/direct response
+    fn first_geocoding_match(resp_json: &JsonValue) -> Option<(f64, f64)> {
+        let first = resp_json.as_array()?.first()?;
+        Some((first.get("lat")?.as_f64()?, first.get("lon")?.as_f64()?))
+    }
+
+    /// Reject a response that has none of the endpoint's expected top-level keys,
+    /// which points at an API change rather than one missing field
+    fn check_response_shape(
+        &self,
+        endpoint_type: EndpointType,
+        resp_json: &JsonValue,
+    ) -> FdwResult {
+        let expected =
+            endpoint_type.expected_response_keys(self.uses_legacy_weather(endpoint_type));
+        if !self.check_response_shape
+            || expected.is_empty()
+            || expected.iter().any(|key| resp_json.get(key).is_some())
+        {
+            return Ok(());
+        }
+        Err(format!(
+            "unexpected response shape for {} (none of {} present); OpenWeather API may have changed. \
+             Set table option check_response_shape 'false' to skip this check",
+            endpoint_type.object_name(),
+            expected.join(", ")
+        ))
+    }
+
+    /// Fetch data from OpenWeather API based on endpoint type
+    fn fetch_source_data(&mut self) -> FdwResult {
+        let endpoint_type = self
+            .endpoint_type
+            .ok_or("endpoint type not set - call begin_scan first")?;
+        self.fetched_at = Some(time::epoch_secs());
+
+        let calls_api = !matches!(
+            endpoint_type,
+            EndpointType::RequestMetadata | EndpointType::ApiUsageLog
+        );
+        if self.dry_run && calls_api {
+            return self.load_dry_run(endpoint_type);
+        }
+
+        // Weather tables queried by city_name alone resolve it to coordinates first
+        if endpoint_type.requires_location() && self.geocode_city_name {
+            self.resolve_city_name()?;
+        }
+
+        // Log request details
+        report_info(&format!(
+            "Fetching OpenWeather data for {:?} at latitude={}, longitude={}",
+            endpoint_type, self.latitude, self.longitude
+        ));
+
+        if endpoint_type == EndpointType::RequestMetadata {
+            self.load_request_metadata()?;
+        } else if endpoint_type == EndpointType::ApiUsageLog {
+            self.data = EndpointData::ApiUsageLog {
+                entries: Self::load_usage_log(),
+            };
+        } else if let (EndpointType::CurrentWeather, Some(as_of)) = (endpoint_type, self.as_of) {
+            self.fetch_current_as_of(as_of)?;
+        } else if endpoint_type == EndpointType::HistoricalWeather {
+            // timemachine serves one timestamp per call, so an IN list issues one call each
+            self.data = EndpointData::HistoricalWeather {
+                latitude: self.latitude,
+                longitude: self.longitude,
+                observations: Vec::with_capacity(self.historical_dts.len()),
+            };
+            for dt in self.historical_dts.clone() {
+                self.dt = Some(dt);
+                let mut resp_json = self.fetch_json(endpoint_type)?;
+                self.check_response_shape(endpoint_type, &resp_json)?;
+                self.apply_description_fallback(endpoint_type, &mut resp_json)?;
+                self.parse_historical_weather(&resp_json)?;
+            }
+        } else if endpoint_type == EndpointType::DailySummary {
+            // day_summary serves one date per call, so a date range issues one call per day
+            self.data = EndpointData::DailySummary {
+                summaries: Vec::with_capacity(self.summary_dates.len()),
+            };
+            for date in self.summary_dates.clone() {
+                self.date = Some(date);
+                let resp_json = self.fetch_json(endpoint_type)?;
+                self.check_response_shape(endpoint_type, &resp_json)?;
+                self.parse_daily_summary(&resp_json)?;
+            }
+        } else {
+            let mut resp_json = self.fetch_json(endpoint_type)?;
+            self.check_response_shape(endpoint_type, &resp_json)?;
+            self.apply_description_fallback(endpoint_type, &mut resp_json)?;
+
+            // Parse response based on endpoint type
+            match endpoint_type {
+                EndpointType::CurrentWeather if self.uses_legacy_weather(endpoint_type) => {
+                    self.parse_legacy_current_weather(&resp_json)?
+                }
+                EndpointType::CurrentWeather => self.parse_current_weather(&resp_json)?,
+                EndpointType::MinutelyForecast => self.parse_minutely_forecast(&resp_json)?,
+                EndpointType::MinutelySummary => self.parse_minutely_summary(&resp_json)?,
+                EndpointType::HourlyForecast => self.parse_hourly_forecast(&resp_json)?,
+                EndpointType::DailyForecast => self.parse_daily_forecast(&resp_json)?,
+                EndpointType::RainOutlook => self.parse_rain_outlook(&resp_json)?,
+                EndpointType::Forecast => self.parse_forecast(&resp_json)?,
+                EndpointType::WeatherAlerts => self.parse_weather_alerts(&resp_json)?,
+                EndpointType::OneCallSummary => self.parse_onecall_summary(&resp_json)?,
+                EndpointType::HistoricalWeather => self.parse_historical_weather(&resp_json)?,
+                EndpointType::DailySummary => self.parse_daily_summary(&resp_json)?,
+                EndpointType::WeatherOverview => self.parse_weather_overview(&resp_json)?,
+                EndpointType::AirPollution => self.parse_air_pollution(&resp_json)?,
+                EndpointType::CitiesInBbox => self.parse_cities_in_bbox(&resp_json)?,
+                EndpointType::CitiesGroup => self.parse_cities_group(&resp_json)?,
+                EndpointType::Geocoding => self.parse_geocoding(&resp_json)?,
+                EndpointType::RequestMetadata => self.load_request_metadata()?,
+                EndpointType::ApiUsageLog => {
+                    return Err("api_usage_log does not call the OpenWeather API".to_string())
+                }
+            }
+
+            if self.uses_onecall(endpoint_type) {
+                self.store_onecall_response(&resp_json)?;
+            }
+        }
+
+        // Track stats
+        let row_count = self.data.row_count();
+        self.inc_stats(stats::Metric::RowsIn, row_count as i64);
+
+        report_info(&format!("Parsed {} rows", row_count));
+
+        // Reset row iterator
+        self.current_row = 0;
+
+        Ok(())
+    }
+}
+
+struct OpenWeatherFdwImpl;
+
+impl Guest for OpenWeatherFdwImpl {
+    fn host_version_requirement() -> String {
+        // Supabase Wrappers version requirement
+        // Compatible with both local (0.1.5) and production (0.2.x+)
+        // This must match WIT declarations in wit/world.wit
+        "^0.1.0".to_string()
+    }
+
+    fn init(ctx: &Context) -> FdwResult {
+        OpenWeatherFdw::init();
+
+        // Extract server options
+        let opts = ctx.get_options(&OptionsType::Server);
+        let instance = OpenWeatherFdw::this_mut();
+
+        // Validate an 'object' given at the server level so typos surface at init
+        if let Some(object_name) = opts.get("object") {
+            EndpointType::from_object_name(&object_name)?;
+        }
+
+        // Get base URL (default to OpenWeather API v3.0)
+        instance.base_url = match opts.get("api_url") {
+            Some(url) => url.clone(),
+            None => "https://api.openweathermap.org/data/3.0".to_string(),
+        };
+
+        // Select the One Call API version, rewriting the '/data/x.y' suffix of the base URL
+        instance.api_version = match opts.get("api_version") {
+            Some(version) => {
+                if !SUPPORTED_API_VERSIONS.contains(&version.as_str()) {
+                    return Err(format!(
+                        "option 'api_version' must be one of {}, got '{}'",
+                        SUPPORTED_API_VERSIONS.join(", "),
+                        version
+                    ));
+                }
+                instance.base_url = format!("{}/data/{}", instance.api_root(), version);
+                version
+            }
+            None if instance.base_url.contains("/data/2.5") => "2.5".to_string(),
+            None => "3.0".to_string(),
+        };
+
+        // Opt-in fast path: current_weather from /weather instead of the full /onecall
+        instance.fast_current = OpenWeatherFdw::bool_option(&opts, "fast_current", false)?;
+        instance.dry_run = OpenWeatherFdw::bool_option(&opts, "dry_run", false)?;
+
+        // Optional coordinate rounding so nearby locations share one API call
+        instance.coord_precision = OpenWeatherFdw::parse_option::<u32>(&opts, "coord_precision")?;
+        if instance
+            .coord_precision
+            .is_some_and(|d| d > MAX_COORD_PRECISION)
+        {
+            return Err(format!(
+                "option 'coord_precision' must be between 0 and {}",
+                MAX_COORD_PRECISION
+            ));
+        }
+
+        // Optional request spacing and jitter to smooth bursts of API calls
+        for key in ["min_request_interval_ms", "request_jitter_ms"] {
+            if OpenWeatherFdw::parse_option::<u64>(&opts, key)?
+                .is_some_and(|ms| ms > MAX_REQUEST_DELAY_MS)
+            {
+                return Err(format!(
+                    "option '{}' must be between 0 and {}",
+                    key, MAX_REQUEST_DELAY_MS
+                ));
+            }
+        }
+        instance.min_request_interval_ms =
+            OpenWeatherFdw::parse_option(&opts, "min_request_interval_ms")?;
+        instance.request_jitter_ms = OpenWeatherFdw::parse_option(&opts, "request_jitter_ms")?;
+
+        // Get API key using Vault (recommended) or plain text (deprecated)
+        instance.server_api_key = if let Some(api_key) = OpenWeatherFdw::api_key_option(&opts)? {
+            api_key
+        } else if OpenWeatherFdw::bool_option(&opts, "allow_empty_api_key", false)? {
+            // Explicit opt-in for mock servers that don't check credentials
+            utils::report_warning(
+                "No API key configured ('allow_empty_api_key' is set). \
+                 Requests will be sent without credentials.",
+            );
+            String::new()
+        } else {
+            return Err(
+                "Either 'api_key' or 'api_key_id' must be provided in server options".to_string(),
+            );
+        };
+
+        // Get optional request timeout
+        instance.request_timeout_ms = OpenWeatherFdw::parse_option(&opts, "request_timeout_ms")?;
+        if instance.request_timeout_ms == Some(0) {
+            return Err("option 'request_timeout_ms' must be greater than 0".to_string());
+        }
+
+        // Get optional default unit system for queries without a 'units' qual
+        instance.default_units = opts
+            .get("default_units")
+            .unwrap_or_else(|| "metric".to_string());
+        if !SUPPORTED_UNITS.contains(&instance.default_units.as_str()) {
+            return Err(format!(
+                "option 'default_units' must be one of {}, got '{}'",
+                SUPPORTED_UNITS.join(", "),
+                instance.default_units
+            ));
+        }
+
+        // Get optional User-Agent override
+        let user_agent = opts
+            .get("user_agent")
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+        if user_agent.trim().is_empty() {
+            return Err("option 'user_agent' must not be empty".to_string());
+        }
+
+        // Set up HTTP headers
+        instance.headers.push(("user-agent".to_owned(), user_agent));
+        // Omitting accept leaves content negotiation to the API (for debugging)
+        if OpenWeatherFdw::bool_option(&opts, "send_accept_header", true)? {
+            instance
+                .headers
+                .push(("accept".to_owned(), "application/json".to_string()));
+        }
+        // Large forecast responses compress well, but the body reaches us as a string,
+        // so only ask for gzip when the host HTTP client is known to decode it
+        if OpenWeatherFdw::bool_option(&opts, "request_gzip", false)? {
+            instance
+                .headers
+                .push(("accept-encoding".to_owned(), "gzip".to_string()));
+        }
+
+        // Extra headers (e.g. for proxies) replace defaults of the same name
+        if let Some(raw) = opts.get("extra_headers") {
+            for (name, value) in OpenWeatherFdw::parse_extra_headers(&raw)? {
+                instance.headers.retain(|(existing, _)| *existing != name);
+                instance.headers.push((name, value));
+            }
+        }
+
+        report_info(&format!(
+            "OpenWeather FDW initialized with URL: {}",
+            instance.base_url
+        ));
+        stats::inc_stats(FDW_NAME, stats::Metric::CreateTimes, 1);
+        Ok(())
+    }
+
+    fn begin_scan(ctx: &Context) -> FdwResult {
+        let instance = OpenWeatherFdw::this_mut();
+
+        // Get table options
+        let opts = ctx.get_options(&OptionsType::Table);
+
+        // A table-level key overrides the server key, so tables can bill different keys
+        instance.api_key = match OpenWeatherFdw::api_key_option(&opts)? {
+            Some(api_key) => api_key,
+            None => instance.server_api_key.clone(),
+        };
+
+        // Parse endpoint type from 'object' option
+        let object_name = opts.get("object").ok_or("'object' option is required")?;
+
+        let endpoint_type = EndpointType::from_object_name(&object_name)?;
+        instance.endpoint_type = Some(endpoint_type);
+        instance.error_on_empty = OpenWeatherFdw::bool_option(&opts, "error_on_empty", true)?;
+        instance.allow_empty = OpenWeatherFdw::bool_option(&opts, "allow_empty", false)?;
+        if instance.allow_empty && endpoint_type != EndpointType::HistoricalWeather {
+            return Err("option 'allow_empty' is only supported on historical_weather".to_string());
+        }
+        instance.strict_row_count = OpenWeatherFdw::bool_option(&opts, "strict_row_count", false)?;
+        instance.strict_fields = OpenWeatherFdw::bool_option(&opts, "strict_fields", false)?;
+        instance.strict_quals = OpenWeatherFdw::bool_option(&opts, "strict_quals", false)?;
+        instance.check_response_shape =
+            OpenWeatherFdw::bool_option(&opts, "check_response_shape", true)?;
+        instance.round_decimals = OpenWeatherFdw::parse_option::<u32>(&opts, "round_decimals")?;
+        if instance
+            .round_decimals
+            .is_some_and(|d| d > MAX_ROUND_DECIMALS)
+        {
+            return Err(format!(
+                "option 'round_decimals' must be between 0 and {}",
+                MAX_ROUND_DECIMALS
+            ));
+        }
+
+        instance.description_fallback_lang = opts.get("description_fallback_lang");
+        instance.explode_weather = OpenWeatherFdw::bool_option(&opts, "explode_weather", false)?;
+        instance.extract_overview_metrics =
+            OpenWeatherFdw::bool_option(&opts, "extract_overview_metrics", false)?;
+        instance.order_alerts = OpenWeatherFdw::bool_option(&opts, "order_alerts", false)?;
+        instance.icon_size = opts.get("icon_size").unwrap_or_else(|| "2x".to_string());
+        if !["1x", "2x", "4x"].contains(&instance.icon_size.as_str()) {
+            return Err(format!(
+                "option 'icon_size' must be '1x', '2x' or '4x', got '{}'",
+                instance.icon_size
+            ));
+        }
+        instance.rain_pop_threshold = OpenWeatherFdw::parse_option(&opts, "rain_pop_threshold")?
+            .unwrap_or(DEFAULT_RAIN_POP_THRESHOLD);
+        if !(0.0..=1.0).contains(&instance.rain_pop_threshold) {
+            return Err("option 'rain_pop_threshold' must be between 0 and 1".to_string());
+        }
+        instance.location_tolerance_deg =
+            OpenWeatherFdw::parse_option::<f64>(&opts, "location_tolerance_deg")?;
+        if instance
+            .location_tolerance_deg
+            .is_some_and(|tolerance| !(0.0..=180.0).contains(&tolerance))
+        {
+            return Err("option 'location_tolerance_deg' must be between 0 and 180".to_string());
+        }
+        instance.rain_horizon_hours = OpenWeatherFdw::parse_option(&opts, "rain_horizon_hours")?
+            .unwrap_or(DEFAULT_RAIN_HORIZON_HOURS);
+        if !(1..=EXPECTED_HOURLY_ROWS as i64).contains(&instance.rain_horizon_hours) {
+            return Err(format!(
+                "option 'rain_horizon_hours' must be between 1 and {}",
+                EXPECTED_HOURLY_ROWS
+            ));
+        }
+        instance.extra_params = opts.get("extra_params");
+        if let Some(ref params) = instance.extra_params {
+            OpenWeatherFdw::validate_extra_params(params)?;
+        }
+        instance.onecall_exclude = match opts.get("include") {
+            Some(include) => OpenWeatherFdw::onecall_exclude(endpoint_type, &include)?,
+            None => None,
+        };
+
+        // Remember the projection so parsers can skip derived values nobody selected
+        instance.projected_columns = ctx.get_columns().iter().map(|c| c.name()).collect();
+
+        // Honour a pushed-down ORDER BY <time column> DESC by emitting rows in reverse
+        instance.descending = endpoint_type.sort_column().is_some_and(|column| {
+            ctx.get_sorts()
+                .first()
+                .is_some_and(|sort| sort.field() == column && sort.reversed())
+        });
+
+        // Extract WHERE clause parameters
+        let quals = ctx.get_quals();
+
+        // Flag parameters meant for another endpoint, e.g. observation_time on
+        // current_weather, which would otherwise silently return live data
+        let qual_fields: Vec<String> = quals.iter().map(|q| q.field()).collect();
+        let ignored = endpoint_type.ignored_parameter_quals(&qual_fields);
+        if !ignored.is_empty() {
+            let msg = format!(
+                "{} ignores WHERE parameter(s) {}; they are not sent to the API",
+                endpoint_type.object_name(),
+                ignored
+                    .iter()
+                    .map(|field| format!("'{}'", field))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            if instance.strict_quals {
+                return Err(msg);
+            }
+            report_info(&msg);
+        }
+
+        // current_weather may be queried by OpenWeather city ID instead of coordinates
+        instance.city_id = match endpoint_type {
+            EndpointType::CurrentWeather => {
+                OpenWeatherFdw::extract_qual_i64(&quals, "city_id", 1..=i64::MAX)?
+            }
+            _ => None,
+        };
+
+        // as_of pins current_weather to a fixed past time, served by the timemachine endpoint
+        instance.as_of =
+            match opts.get("as_of") {
+                Some(_) if endpoint_type != EndpointType::CurrentWeather => {
+                    return Err("option 'as_of' is only supported on current_weather".to_string())
+                }
+                Some(_) if instance.city_id.is_some() => return Err(
+                    "option 'as_of' needs latitude/longitude; it cannot be combined with city_id"
+                        .to_string(),
+                ),
+                Some(text) => Some(OpenWeatherFdw::validate_as_of(&text)?),
+                None => None,
+            };
+
+        // weather_alerts can be narrowed to alerts overlapping a time window
+        (instance.alert_start_range, instance.alert_end_range) = match endpoint_type {
+            EndpointType::WeatherAlerts => (
+                OpenWeatherFdw::extract_qual_timestamptz_range(&quals, "alert_start_time"),
+                OpenWeatherFdw::extract_qual_timestamptz_range(&quals, "alert_end_time"),
+            ),
+            _ => (None, None),
+        };
+
+        // Extract and validate location (required for all weather endpoints), from
+        // latitude/longitude quals or a WKT point location qual. Without either, a
+        // city_name qual is geocoded in fetch_source_data
+        if endpoint_type.requires_location() && instance.city_id.is_none() {
+            let has_coordinates = quals
+                .iter()
+                .any(|q| matches!(q.field().as_str(), "latitude" | "longitude" | "location"));
+            instance.location = OpenWeatherFdw::extract_qual_string(&quals, "location");
+            // A city_name next to coordinates is only echoed back, not geocoded
+            instance.city_name = OpenWeatherFdw::extract_qual_string(&quals, "city_name");
+            instance.geocode_city_name = instance.city_name.is_some() && !has_coordinates;
+            if !instance.geocode_city_name {
+                let (latitude, longitude) = OpenWeatherFdw::extract_and_validate_location(&quals)?;
+                instance.latitude = latitude;
+                instance.longitude = longitude;
+            }
+        }
+
+        // Extract optional parameters with defaults
+        instance.units = OpenWeatherFdw::extract_qual_string(&quals, "units")
+            .unwrap_or_else(|| instance.default_units.clone());
+        instance.lang =
+            OpenWeatherFdw::extract_qual_string(&quals, "lang").unwrap_or_else(|| "en".to_string());
+
+        // day_summary and overview only exist in One Call API 3.0
+        if instance.api_version == "2.5"
+            && matches!(
+                endpoint_type,
+                EndpointType::DailySummary | EndpointType::WeatherOverview
+            )
+        {
+            return Err(format!(
+                "{} requires One Call API 3.0; set server option api_version '3.0'",
+                endpoint_type.object_name()
+            ));
+        }
+
+        // Extract endpoint-specific parameters
         match endpoint_type {
             EndpointType::HistoricalWeather => {
-                // Extract observation_time and convert to Unix seconds for API
-                let observation_time = OpenWeatherFdw::extract_qual_timestamptz(&quals, "observation_time")
-                    .ok_or(
-                        "WHERE clause must include 'observation_time' for historical_weather.\n\
-                         Use a literal timestamp value in the format 'YYYY-MM-DD HH:MM:SS+00'.\n\
-                         \n\
-                         Example: WHERE observation_time = '2024-10-28 00:00:00+00'\n\
-                         \n\
-                         Note: Computed expressions (NOW(), INTERVAL, etc.) are not supported in WHERE clauses.\n\
-                         Calculate timestamps in your application before querying."
-                    )?;
-                instance.dt = Some(observation_time / 1_000_000); // Convert microseconds → seconds for API
+                // Extract observation_time (one value or an IN list) and convert to Unix
+                // seconds for API, falling back to a raw epoch-seconds 'dt' qual
+                let mut dts: Vec<i64> =
+                    OpenWeatherFdw::extract_qual_timestamptz(&quals, "observation_time")
+                        .into_iter()
+                        .map(|observation_time| observation_time / 1_000_000) // Convert microseconds → seconds for API
+                        .collect();
+                dts.sort_unstable();
+                dts.dedup();
+                if dts.len() > MAX_HISTORICAL_TIMESTAMPS {
+                    return Err(format!(
+                        "observation_time IN list has {} timestamps; maximum is {} (one API call per timestamp)",
+                        dts.len(),
+                        MAX_HISTORICAL_TIMESTAMPS
+                    ));
+                }
+                if dts.is_empty() {
+                    let dt = OpenWeatherFdw::extract_qual_i64(&quals, "dt", i64::MIN..=i64::MAX)?
+                        .ok_or(
+                            "WHERE clause must include 'observation_time' (or 'dt' in Unix seconds) for historical_weather.\n\
+                             Use a literal timestamp value in the format 'YYYY-MM-DD HH:MM:SS+00'.\n\
+                             \n\
+                             Example: WHERE observation_time = '2024-10-28 00:00:00+00'\n\
+                             Example: WHERE dt = 1730073600\n\
+                             \n\
+                             Note: Computed expressions (NOW(), INTERVAL, etc.) are not supported in WHERE clauses.\n\
+                             Calculate timestamps in your application before querying."
+                        )?;
+                    dts.push(dt);
+                }
+                for &dt in &dts {
+                    OpenWeatherFdw::validate_historical_dt(dt)?;
+                }
+                instance.historical_dts = dts;
             }
             EndpointType::DailySummary => {
-                // Extract required summary_date parameter (YYYY-MM-DD)
-                instance.date = Some(OpenWeatherFdw::extract_qual_string(&quals, "summary_date")
-                    .ok_or("WHERE clause must include 'summary_date' (YYYY-MM-DD format) for daily_summary. Example: WHERE latitude = 52.52 AND longitude = 13.405 AND summary_date = '2024-01-15'")?);
-                // Extract optional timezone_offset parameter (+/-HHMM)
+                // Extract required summary_date parameter (YYYY-MM-DD, or a range of dates)
+                instance.summary_dates = OpenWeatherFdw::extract_summary_dates(&quals)?;
+                // Extract optional timezone_offset parameter (±HH:MM, or ±HHMM)
                 instance.timezone_offset =
                     OpenWeatherFdw::extract_qual_string(&quals, "timezone_offset");
+                if let Some(ref tz) = instance.timezone_offset {
+                    OpenWeatherFdw::normalize_timezone_offset(tz)?;
+                }
             }
             EndpointType::WeatherOverview => {
                 // Extract optional overview_date parameter (defaults to today if omitted)
                 instance.date = OpenWeatherFdw::extract_qual_string(&quals, "overview_date");
+                if let Some(ref date) = instance.date {
+                    OpenWeatherFdw::validate_date("overview_date", date)?;
+                }
+            }
+            EndpointType::CitiesInBbox => {
+                // Extract required bounding box and optional zoom level
+                instance.bbox = Some(OpenWeatherFdw::extract_bbox(&quals)?);
+            }
+            EndpointType::CitiesGroup => {
+                // Extract required city IDs (comma-separated text or an IN list)
+                (instance.group_city_ids, instance.group_city_ids_text) =
+                    OpenWeatherFdw::extract_group_city_ids(&quals)?;
+            }
+            EndpointType::Geocoding => {
+                // Extract location name and optional match limit (1-5)
+                instance.city_name = OpenWeatherFdw::extract_qual_string(&quals, "city_name");
+                instance.geocoding_limit = OpenWeatherFdw::extract_geocoding_limit(&quals)?;
             }
             _ => {} // No additional parameters needed for other endpoints
         }
@@ -2042,17 +6310,14 @@ impl Guest for OpenWeatherFdwImpl {
 
         // Check if we've exhausted all rows
         if instance.current_row >= instance.data.row_count() {
-            stats::inc_stats(
-                FDW_NAME,
-                stats::Metric::RowsOut,
-                instance.current_row as i64,
-            );
+            instance.inc_stats(stats::Metric::RowsOut, instance.current_row as i64);
             return Ok(None);
         }
 
         // Populate row with values from current index
         for tgt_col in ctx.get_columns() {
-            let cell = instance.get_cell_value(&tgt_col)?;
+            let cell = instance.get_cell_value(&tgt_col.name())?;
+            let cell = OpenWeatherFdw::cast_numeric(cell, tgt_col.type_oid());
             row.push(cell.as_ref());
         }
 
@@ -2068,28 +6333,51 @@ impl Guest for OpenWeatherFdwImpl {
         instance.endpoint_type = None;
         instance.data = EndpointData::None;
         instance.current_row = 0;
+        instance.descending = false;
 
         Ok(())
     }
 
-    fn begin_modify(_ctx: &Context) -> FdwResult {
-        Err("OpenWeather FDW does not support data modification".to_string())
+    fn begin_modify(ctx: &Context) -> FdwResult {
+        let instance = OpenWeatherFdw::this_mut();
+        let opts = ctx.get_options(&OptionsType::Table);
+        let object_name = opts.get("object").ok_or("'object' option is required")?;
+
+        // Only the api_usage_log endpoint is writable; OpenWeather itself is read-only
+        if EndpointType::from_object_name(&object_name)? != EndpointType::ApiUsageLog {
+            return Err(format!(
+                "OpenWeather FDW endpoint '{}' is read-only; only api_usage_log accepts INSERT",
+                object_name
+            ));
+        }
+        instance.endpoint_type = Some(EndpointType::ApiUsageLog);
+        instance.usage_log = OpenWeatherFdw::load_usage_log();
+        Ok(())
     }
 
-    fn insert(_ctx: &Context, _row: &Row) -> FdwResult {
-        Err("OpenWeather FDW does not support INSERT".to_string())
+    fn insert(_ctx: &Context, row: &Row) -> FdwResult {
+        OpenWeatherFdw::this_mut().record_usage(row)
     }
 
     fn update(_ctx: &Context, _rowid: Cell, _row: &Row) -> FdwResult {
-        Err("OpenWeather FDW does not support UPDATE".to_string())
+        Err(
+            "OpenWeather FDW endpoint 'api_usage_log' does not support UPDATE, only INSERT"
+                .to_string(),
+        )
     }
 
     fn delete(_ctx: &Context, _rowid: Cell) -> FdwResult {
-        Err("OpenWeather FDW does not support DELETE".to_string())
+        Err(
+            "OpenWeather FDW endpoint 'api_usage_log' does not support DELETE, only INSERT"
+                .to_string(),
+        )
     }
 
     fn end_modify(_ctx: &Context) -> FdwResult {
-        Err("OpenWeather FDW does not support data modification".to_string())
+        let instance = OpenWeatherFdw::this_mut();
+        instance.save_usage_log();
+        instance.usage_log.clear();
+        Ok(())
     }
 
     fn re_scan(_ctx: &Context) -> FdwResult {
@@ -2098,32 +6386,79 @@ impl Guest for OpenWeatherFdwImpl {
     }
 
     fn import_foreign_schema(
-        _ctx: &Context,
+        ctx: &Context,
         stmt: ImportForeignSchemaStmt,
     ) -> Result<Vec<String>, FdwError> {
-        // Generate schemas for all 8 supported endpoints (v0.3.0 - standards compliant)
+        // Optional prefix for generated table names (object option stays the endpoint name)
+        let import_opts = ctx.get_options(&OptionsType::ImportSchema);
+        let table_prefix = import_opts.get("table_prefix").unwrap_or_default();
+        if !table_prefix
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(format!(
+                "import option 'table_prefix' may only contain lowercase letters, digits and underscores, got '{}'",
+                table_prefix
+            ));
+        }
+        // Optional fetched_at column on every table, for ETL bookkeeping
+        let with_fetch_time = OpenWeatherFdw::bool_option(&import_opts, "with_fetch_time", false)?;
+        // Column type for numeric fields
+        let numeric_type = import_opts
+            .get("numeric_type")
+            .unwrap_or_else(|| "numeric".to_string());
+        if !SUPPORTED_NUMERIC_TYPES.contains(&numeric_type.as_str()) {
+            return Err(format!(
+                "import option 'numeric_type' must be one of {}, got '{}'",
+                SUPPORTED_NUMERIC_TYPES.join(", "),
+                numeric_type
+            ));
+        }
+
+        // Generate schemas for all supported endpoints (v0.3.0 - standards compliant)
         let ret = vec![
             // current_weather table (1 row from /onecall → current)
             format!(
                 r#"create foreign table if not exists current_weather (
+                city_id bigint,
                 latitude numeric,
                 longitude numeric,
+                city_name text,
+                location text,
                 timezone_name text,
+                timezone_offset_seconds bigint,
                 observation_time timestamp with time zone,
+                observation_time_local timestamp without time zone,
+                forecast_age_seconds bigint,
+                sunrise_time timestamp with time zone,
+                sunset_time timestamp with time zone,
                 temperature_temp numeric,
                 apparent_temperature_temp numeric,
-                pressure_hpa numeric,
-                humidity_pct numeric,
+                apparent_temp_delta numeric,
+                pressure_hpa bigint,
+                pressure_inhg numeric,
+                humidity_pct bigint,
                 dew_point_temp numeric,
                 uv_index numeric,
-                cloud_cover_pct numeric,
-                visibility_m numeric,
+                uv_index_risk text,
+                cloud_cover_pct bigint,
+                visibility_m bigint,
+                visibility_km numeric,
+                visibility_mi numeric,
                 wind_speed_m_s numeric,
-                wind_direction_deg numeric,
+                wind_speed_kt numeric,
+                wind_direction_deg bigint,
                 wind_gust_speed_m_s numeric,
                 weather_condition text,
+                weather_condition_code bigint,
                 weather_description text,
-                weather_icon_code text
+                weather_icon_code text,
+                weather_icon_url text,
+                is_daytime boolean,
+                weather_all jsonb,
+                temperature_trend text,
+                active_alerts_count bigint,
+                has_active_alerts boolean
             )
             server {} options (
                 object 'current_weather'
@@ -2135,6 +6470,8 @@ impl Guest for OpenWeatherFdwImpl {
                 r#"create foreign table if not exists minutely_forecast (
                 latitude numeric,
                 longitude numeric,
+                city_name text,
+                location text,
                 forecast_time timestamp with time zone,
                 precipitation_mm numeric
             )
@@ -2143,29 +6480,111 @@ impl Guest for OpenWeatherFdwImpl {
             )"#,
                 stmt.server_name,
             ),
+            // minutely_summary table (1 row aggregated from /onecall → minutely[])
+            format!(
+                r#"create foreign table if not exists minutely_summary (
+                latitude numeric,
+                longitude numeric,
+                city_name text,
+                location text,
+                total_precip_mm numeric,
+                minutes_until_precip bigint,
+                peak_precip_mm numeric
+            )
+            server {} options (
+                object 'minutely_summary'
+            )"#,
+                stmt.server_name,
+            ),
+            // rain_outlook table (1 row aggregated from /onecall → hourly[])
+            format!(
+                r#"create foreign table if not exists rain_outlook (
+                latitude numeric,
+                longitude numeric,
+                city_name text,
+                location text,
+                horizon_hours bigint,
+                pop_threshold numeric,
+                will_rain boolean,
+                rain_starts_at timestamp with time zone,
+                max_pop numeric
+            )
+            server {} options (
+                object 'rain_outlook'
+            )"#,
+                stmt.server_name,
+            ),
+            // forecast table (hourly[] and daily[] rows from /onecall, by granularity)
+            format!(
+                r#"create foreign table if not exists forecast (
+                latitude numeric,
+                longitude numeric,
+                city_name text,
+                location text,
+                granularity text,
+                forecast_time timestamp with time zone,
+                temperature_temp numeric,
+                temperature_min_temp numeric,
+                temperature_max_temp numeric,
+                pressure_hpa bigint,
+                humidity_pct bigint,
+                wind_speed_m_s numeric,
+                precipitation_probability numeric,
+                rain_volume_mm numeric,
+                snow_volume_mm numeric,
+                visibility_m bigint,
+                weather_condition text,
+                weather_condition_code bigint,
+                weather_description text,
+                weather_icon_code text,
+                weather_icon_url text
+            )
+            server {} options (
+                object 'forecast'
+            )"#,
+                stmt.server_name,
+            ),
             // hourly_forecast table (48 rows from /onecall → hourly[])
             format!(
                 r#"create foreign table if not exists hourly_forecast (
                 latitude numeric,
                 longitude numeric,
+                city_name text,
+                location text,
+                timezone_offset_seconds bigint,
                 forecast_time timestamp with time zone,
+                forecast_time_local timestamp without time zone,
                 temperature_temp numeric,
                 apparent_temperature_temp numeric,
-                pressure_hpa numeric,
-                humidity_pct numeric,
+                apparent_temp_delta numeric,
+                pressure_hpa bigint,
+                pressure_inhg numeric,
+                humidity_pct bigint,
                 dew_point_temp numeric,
                 uv_index numeric,
-                cloud_cover_pct numeric,
-                visibility_m numeric,
+                uv_index_risk text,
+                cloud_cover_pct bigint,
+                visibility_m bigint,
+                visibility_km numeric,
+                visibility_mi numeric,
                 wind_speed_m_s numeric,
-                wind_direction_deg numeric,
+                wind_speed_kt numeric,
+                wind_direction_deg bigint,
                 wind_gust_speed_m_s numeric,
                 precipitation_probability numeric,
+                precipitation_probability_pct bigint,
                 rain_volume_1h_mm numeric,
                 snow_volume_1h_mm numeric,
+                rain_volume_1h_in numeric,
+                snow_volume_1h_in numeric,
+                precipitation_type text,
                 weather_condition text,
+                weather_condition_code bigint,
                 weather_description text,
-                weather_icon_code text
+                weather_icon_code text,
+                weather_icon_url text,
+                is_daytime boolean,
+                weather_all jsonb
             )
             server {} options (
                 object 'hourly_forecast'
@@ -2177,15 +6596,24 @@ impl Guest for OpenWeatherFdwImpl {
                 r#"create foreign table if not exists daily_forecast (
                 latitude numeric,
                 longitude numeric,
+                city_name text,
+                location text,
+                timezone_offset_seconds bigint,
                 forecast_date timestamp with time zone,
+                forecast_date_local timestamp without time zone,
                 sunrise_time timestamp with time zone,
+                sunrise_time_local timestamp without time zone,
                 sunset_time timestamp with time zone,
+                sunset_time_local timestamp without time zone,
                 moonrise_time timestamp with time zone,
                 moonset_time timestamp with time zone,
                 moon_phase_fraction numeric,
+                moon_phase_name text,
+                moon_illumination_pct numeric,
                 temperature_day_temp numeric,
                 temperature_min_temp numeric,
                 temperature_max_temp numeric,
+                temperature_range_temp numeric,
                 temperature_night_temp numeric,
                 temperature_evening_temp numeric,
                 temperature_morning_temp numeric,
@@ -2193,20 +6621,30 @@ impl Guest for OpenWeatherFdwImpl {
                 apparent_temperature_night_temp numeric,
                 apparent_temperature_evening_temp numeric,
                 apparent_temperature_morning_temp numeric,
-                pressure_hpa numeric,
-                humidity_pct numeric,
+                pressure_hpa bigint,
+                pressure_inhg numeric,
+                humidity_pct bigint,
                 dew_point_temp numeric,
                 wind_speed_m_s numeric,
-                wind_direction_deg numeric,
+                wind_speed_kt numeric,
+                wind_direction_deg bigint,
                 wind_gust_speed_m_s numeric,
-                cloud_cover_pct numeric,
+                cloud_cover_pct bigint,
                 precipitation_probability numeric,
+                precipitation_probability_pct bigint,
                 rain_volume_mm numeric,
                 snow_volume_mm numeric,
+                rain_volume_in numeric,
+                snow_volume_in numeric,
                 uv_index numeric,
+                uv_index_risk text,
                 weather_condition text,
+                weather_condition_code bigint,
                 weather_description text,
-                weather_icon_code text
+                weather_icon_code text,
+                weather_icon_url text,
+                is_daytime boolean,
+                day_summary_text text
             )
             server {} options (
                 object 'daily_forecast'
@@ -2218,51 +6656,95 @@ impl Guest for OpenWeatherFdwImpl {
                 r#"create foreign table if not exists weather_alerts (
                 latitude numeric,
                 longitude numeric,
+                city_name text,
+                location text,
                 alert_sender_name text,
                 alert_event_type text,
+                alert_event_normalized text,
                 alert_start_time timestamp with time zone,
                 alert_end_time timestamp with time zone,
+                alert_duration_seconds bigint,
                 alert_description text,
-                alert_tags text
+                alert_description_lang text,
+                alert_tags text,
+                alert_severity text
             )
             server {} options (
                 object 'weather_alerts'
             )"#,
                 stmt.server_name,
             ),
-            // historical_weather table (1 row from /onecall/timemachine → data[0])
+            // fetch_all_onecall table (1 row combining /onecall sections)
             format!(
-                r#"create foreign table if not exists historical_weather (
+                r#"create foreign table if not exists fetch_all_onecall (
                 latitude numeric,
                 longitude numeric,
+                city_name text,
+                location text,
+                timezone_name text,
                 observation_time timestamp with time zone,
                 temperature_temp numeric,
                 apparent_temperature_temp numeric,
-                pressure_hpa numeric,
-                humidity_pct numeric,
-                dew_point_temp numeric,
-                cloud_cover_pct numeric,
-                visibility_m numeric,
+                humidity_pct bigint,
                 wind_speed_m_s numeric,
-                wind_direction_deg numeric,
                 weather_condition text,
                 weather_description text,
-                weather_icon_code text
+                next_hour_precip_mm numeric,
+                today_min_temp numeric,
+                today_max_temp numeric,
+                active_alerts_count bigint,
+                has_active_alerts boolean
             )
             server {} options (
-                object 'historical_weather'
+                object 'fetch_all_onecall'
             )"#,
                 stmt.server_name,
             ),
-            // daily_summary table (1 row from /onecall/day_summary → daily aggregations)
+            // historical_weather table (1 row from /onecall/timemachine → data[0])
             format!(
-                r#"create foreign table if not exists daily_summary (
+                r#"create foreign table if not exists historical_weather (
                 latitude numeric,
                 longitude numeric,
-                timezone_offset text,
-                summary_date text,
-                unit_system text,
-                temperature_min_temp numeric,
+                city_name text,
+                location text,
+                observation_time timestamp with time zone,
+                dt bigint,
+                temperature_temp numeric,
+                apparent_temperature_temp numeric,
+                apparent_temp_delta numeric,
+                pressure_hpa bigint,
+                pressure_inhg numeric,
+                humidity_pct bigint,
+                dew_point_temp numeric,
+                cloud_cover_pct bigint,
+                visibility_m bigint,
+                visibility_km numeric,
+                visibility_mi numeric,
+                wind_speed_m_s numeric,
+                wind_direction_deg bigint,
+                weather_condition text,
+                weather_condition_code bigint,
+                weather_description text,
+                weather_icon_code text,
+                weather_icon_url text,
+                is_daytime boolean
+            )
+            server {} options (
+                object 'historical_weather'
+            )"#,
+                stmt.server_name,
+            ),
+            // daily_summary table (1 row from /onecall/day_summary → daily aggregations)
+            format!(
+                r#"create foreign table if not exists daily_summary (
+                latitude numeric,
+                longitude numeric,
+                city_name text,
+                location text,
+                timezone_offset text,
+                summary_date text,
+                unit_system text,
+                temperature_min_temp numeric,
                 temperature_max_temp numeric,
                 temperature_morning_temp numeric,
                 temperature_afternoon_temp numeric,
@@ -2273,7 +6755,8 @@ impl Guest for OpenWeatherFdwImpl {
                 pressure_afternoon_hpa numeric,
                 precipitation_total_mm numeric,
                 wind_max_speed_m_s numeric,
-                wind_max_direction_deg numeric
+                wind_max_direction_deg numeric,
+                api_message text
             )
             server {} options (
                 object 'daily_summary'
@@ -2285,20 +6768,1810 @@ impl Guest for OpenWeatherFdwImpl {
                 r#"create foreign table if not exists weather_overview (
                 latitude numeric,
                 longitude numeric,
+                city_name text,
+                location text,
                 timezone_offset text,
                 overview_date text,
                 unit_system text,
-                weather_overview text
+                weather_overview text,
+                mentioned_high_temp numeric,
+                mentioned_low_temp numeric,
+                api_message text
             )
             server {} options (
                 object 'weather_overview'
             )"#,
                 stmt.server_name,
             ),
+            // air_pollution table (1 row from /data/2.5/air_pollution → list[0])
+            format!(
+                r#"create foreign table if not exists air_pollution (
+                latitude numeric,
+                longitude numeric,
+                city_name text,
+                location text,
+                observation_time timestamp with time zone,
+                aqi bigint,
+                aqi_label text,
+                co_ug_m3 numeric,
+                no_ug_m3 numeric,
+                no2_ug_m3 numeric,
+                o3_ug_m3 numeric,
+                so2_ug_m3 numeric,
+                pm2_5_ug_m3 numeric,
+                pm10_ug_m3 numeric,
+                nh3_ug_m3 numeric
+            )
+            server {} options (
+                object 'air_pollution'
+            )"#,
+                stmt.server_name,
+            ),
+            // cities_in_bbox table (0-N rows from /data/2.5/box/city → list[])
+            format!(
+                r#"create foreign table if not exists cities_in_bbox (
+                min_lat numeric,
+                min_lon numeric,
+                max_lat numeric,
+                max_lon numeric,
+                zoom bigint,
+                city_id bigint,
+                city_name text,
+                latitude numeric,
+                longitude numeric,
+                observation_time timestamp with time zone,
+                temperature_temp numeric,
+                apparent_temperature_temp numeric,
+                pressure_hpa bigint,
+                humidity_pct bigint,
+                cloud_cover_pct bigint,
+                wind_speed_m_s numeric,
+                wind_direction_deg bigint,
+                weather_condition text,
+                weather_condition_code bigint,
+                weather_description text,
+                weather_icon_code text,
+                weather_icon_url text,
+                is_daytime boolean
+            )
+            server {} options (
+                object 'cities_in_bbox'
+            )"#,
+                stmt.server_name,
+            ),
+            // cities_group table (1-20 rows from /data/2.5/group → list[])
+            format!(
+                r#"create foreign table if not exists cities_group (
+                city_ids text,
+                city_id bigint,
+                city_name text,
+                latitude numeric,
+                longitude numeric,
+                observation_time timestamp with time zone,
+                temperature_temp numeric,
+                apparent_temperature_temp numeric,
+                pressure_hpa bigint,
+                humidity_pct bigint,
+                cloud_cover_pct bigint,
+                wind_speed_m_s numeric,
+                wind_direction_deg bigint,
+                weather_condition text,
+                weather_condition_code bigint,
+                weather_description text,
+                weather_icon_code text,
+                weather_icon_url text,
+                is_daytime boolean
+            )
+            server {} options (
+                object 'cities_group'
+            )"#,
+                stmt.server_name,
+            ),
+            // geocoding table (0-5 rows from /geo/1.0/direct)
+            format!(
+                r#"create foreign table if not exists geocoding (
+                city_name text,
+                "limit" bigint,
+                location_name text,
+                latitude numeric,
+                longitude numeric,
+                country_code text,
+                state_name text
+            )
+            server {} options (
+                object 'geocoding'
+            )"#,
+                stmt.server_name,
+            ),
+            // request_metadata table (0-1 rows, last API request details)
+            format!(
+                r#"create foreign table if not exists request_metadata (
+                endpoint text,
+                request_url text,
+                status_code bigint,
+                response_bytes bigint,
+                request_count bigint,
+                latency_ms bigint,
+                recommended_refresh_seconds bigint
+            )
+            server {} options (
+                object 'request_metadata'
+            )"#,
+                stmt.server_name,
+            ),
+            // api_usage_log table (writable audit counters persisted via stats metadata)
+            format!(
+                r#"create foreign table if not exists api_usage_log (
+                endpoint text,
+                latitude numeric,
+                longitude numeric,
+                logged_count bigint,
+                last_logged_time timestamp with time zone
+            )
+            server {} options (
+                object 'api_usage_log',
+                rowid_column 'endpoint'
+            )"#,
+                stmt.server_name,
+            ),
         ];
+
+        // Honor IMPORT FOREIGN SCHEMA ... LIMIT TO (...) / EXCEPT (...), matching
+        // prefixed names as PostgreSQL does when it re-checks the returned tables
+        let ret = ret
+            .into_iter()
+            .map(|ddl| {
+                let ddl = ddl
+                    .replacen(
+                        "if not exists ",
+                        &format!("if not exists {}", table_prefix),
+                        1,
+                    )
+                    .replace(" numeric,\n", &format!(" {},\n", numeric_type))
+                    .replace(" numeric\n", &format!(" {}\n", numeric_type));
+                if with_fetch_time {
+                    ddl.replacen(
+                        "\n            )\n            server ",
+                        ",\n                fetched_at timestamp with time zone\n            )\n            server ",
+                        1,
+                    )
+                } else {
+                    ddl
+                }
+            })
+            .filter(|ddl| {
+                let listed = OpenWeatherFdw::ddl_table_name(ddl)
+                    .is_some_and(|name| stmt.table_list.iter().any(|t| t == name));
+                match stmt.list_type {
+                    ImportSchemaType::All => true,
+                    ImportSchemaType::LimitTo => listed,
+                    ImportSchemaType::Except => !listed,
+                }
+            })
+            .collect();
         Ok(ret)
     }
 }
 
 // Export the implementation
 bindings::export!(OpenWeatherFdwImpl with_types_in bindings);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build an instance as begin_scan would for a Berlin query selecting all columns
+    fn scan(endpoint_type: EndpointType) -> OpenWeatherFdw {
+        OpenWeatherFdw {
+            endpoint_type: Some(endpoint_type),
+            latitude: 52.52,
+            longitude: 13.405,
+            units: "metric".to_string(),
+            lang: "en".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Load a JSON response fixture from tests/fixtures
+    macro_rules! fixture {
+        ($name:literal) => {
+            serde_json::from_str::<JsonValue>(include_str!(concat!(
+                "../tests/fixtures/",
+                $name,
+                ".json"
+            )))
+            .unwrap()
+        };
+    }
+
+    /// A /onecall 'weather' array entry
+    fn weather() -> JsonValue {
+        serde_json::json!([{"id": 800, "main": "Clear", "description": "clear sky", "icon": "01d"}])
+    }
+
+    #[test]
+    fn current_weather_missing_wind_gust_is_null() {
+        let resp = serde_json::json!({
+            "timezone": "Europe/Berlin",
+            "timezone_offset": 3600,
+            "current": {
+                "dt": 1_700_000_000, "temp": 10.5, "feels_like": 9.0, "pressure": 1013,
+                "humidity": 80, "dew_point": 7.0, "uvi": 0.5, "clouds": 0,
+                "visibility": 10000, "wind_speed": 3.2, "wind_deg": 180,
+                "weather": weather()
+            }
+        });
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.parse_current_weather(&resp).unwrap();
+
+        assert!(matches!(
+            fdw.get_cell_value("wind_gust_speed_m_s"),
+            Ok(None)
+        ));
+        assert!(matches!(
+            fdw.get_cell_value("wind_speed_m_s"),
+            Ok(Some(Cell::Numeric(v))) if v == 3.2
+        ));
+    }
+
+    #[test]
+    fn current_weather_missing_uvi_depends_on_strict_fields() {
+        let resp = serde_json::json!({
+            "timezone_offset": 3600,
+            "current": {
+                "dt": 1_700_000_000, "temp": 10.5, "feels_like": 9.0, "humidity": 80,
+                "clouds": 0, "wind_speed": 3.2, "wind_deg": 180, "weather": weather()
+            }
+        });
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.parse_current_weather(&resp).unwrap();
+        assert!(matches!(fdw.get_cell_value("uv_index"), Ok(None)));
+        assert!(matches!(fdw.get_cell_value("dew_point_temp"), Ok(None)));
+        assert!(matches!(fdw.get_cell_value("pressure_hpa"), Ok(None)));
+
+        fdw.strict_fields = true;
+        assert_eq!(
+            fdw.parse_current_weather(&resp).unwrap_err(),
+            "missing 'pressure' in current"
+        );
+    }
+
+    #[test]
+    fn legacy_current_weather_missing_wind_gust_is_null() {
+        let resp = serde_json::json!({
+            "id": 2950159,
+            "coord": {"lat": 52.52, "lon": 13.405},
+            "timezone": 3600,
+            "dt": 1_700_000_000,
+            "main": {"temp": 10.5, "feels_like": 9.0, "pressure": 1013, "humidity": 80},
+            "visibility": 10000,
+            "wind": {"speed": 3.2, "deg": 180},
+            "clouds": {"all": 0},
+            "weather": weather()
+        });
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.city_id = Some(2950159);
+        fdw.parse_legacy_current_weather(&resp).unwrap();
+
+        assert!(matches!(
+            fdw.get_cell_value("wind_gust_speed_m_s"),
+            Ok(None)
+        ));
+
+        // Required fields are reported, not defaulted to 0
+        let mut resp = resp;
+        resp["wind"].as_object_mut().unwrap().remove("deg");
+        assert_eq!(
+            fdw.parse_legacy_current_weather(&resp).unwrap_err(),
+            "missing 'wind.deg' in /weather response"
+        );
+    }
+
+    #[test]
+    fn hourly_forecast_missing_wind_gust_is_null() {
+        let hour = |dt: i64, gust: Option<f64>| {
+            let mut hour = serde_json::json!({
+                "dt": dt, "temp": 10.0, "feels_like": 9.0, "pressure": 1013, "humidity": 80,
+                "dew_point": 7.0, "uvi": 0.5, "clouds": 0, "visibility": 10000,
+                "wind_speed": 3.2, "wind_deg": 180, "pop": 0.1, "weather": weather()
+            });
+            if let Some(gust) = gust {
+                hour["wind_gust"] = serde_json::json!(gust);
+            }
+            hour
+        };
+        let resp = serde_json::json!({
+            "timezone_offset": 3600,
+            "hourly": [hour(1_700_000_000, Some(5.5)), hour(1_700_003_600, None)]
+        });
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        fdw.parse_hourly_forecast(&resp).unwrap();
+
+        assert!(matches!(
+            fdw.get_cell_value("wind_gust_speed_m_s"),
+            Ok(Some(Cell::Numeric(v))) if v == 5.5
+        ));
+        fdw.current_row = 1;
+        assert!(matches!(
+            fdw.get_cell_value("wind_gust_speed_m_s"),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn hourly_forecast_missing_visibility_is_null() {
+        let resp = serde_json::json!({
+            "timezone_offset": 3600,
+            "hourly": [{
+                "dt": 1_700_000_000, "temp": 10.0, "feels_like": 9.0, "pressure": 1013,
+                "humidity": 80, "dew_point": 7.0, "uvi": 0.5, "clouds": 0,
+                "wind_speed": 3.2, "wind_deg": 180, "pop": 0.1, "weather": weather()
+            }]
+        });
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        fdw.parse_hourly_forecast(&resp).unwrap();
+
+        assert!(matches!(fdw.get_cell_value("visibility_m"), Ok(None)));
+        assert!(matches!(fdw.get_cell_value("visibility_km"), Ok(None)));
+        assert!(matches!(fdw.get_cell_value("visibility_mi"), Ok(None)));
+    }
+
+    #[test]
+    fn daily_forecast_missing_wind_gust_is_null() {
+        let resp = serde_json::json!({
+            "timezone_offset": 3600,
+            "daily": [{
+                "dt": 1_700_000_000, "sunrise": 1_699_990_000, "sunset": 1_700_020_000,
+                "moonrise": 1_699_995_000, "moonset": 1_700_030_000, "moon_phase": 0.5,
+                "temp": {"day": 10.0, "min": 5.0, "max": 12.0, "night": 6.0, "eve": 9.0, "morn": 5.5},
+                "feels_like": {"day": 9.0, "night": 5.0, "eve": 8.0, "morn": 4.5},
+                "pressure": 1013, "humidity": 80, "dew_point": 7.0,
+                "wind_speed": 3.2, "wind_deg": 180, "clouds": 0, "pop": 0.1, "uvi": 0.5,
+                "weather": weather()
+            }]
+        });
+        let mut fdw = scan(EndpointType::DailyForecast);
+        fdw.parse_daily_forecast(&resp).unwrap();
+
+        assert!(matches!(
+            fdw.get_cell_value("wind_gust_speed_m_s"),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn unknown_object_suggests_closest_endpoint() {
+        let err = EndpointType::from_object_name("daily_forcast").unwrap_err();
+        assert!(err.contains("Did you mean 'daily_forecast'?"), "{}", err);
+
+        let err = EndpointType::from_object_name("something_else").unwrap_err();
+        assert!(!err.contains("Did you mean"), "{}", err);
+    }
+
+    #[test]
+    fn get_request_has_no_body() {
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        fdw.base_url = "https://api.openweathermap.org/data/3.0".to_string();
+        let req = fdw.create_request().unwrap();
+
+        assert!(matches!(req.method, http::Method::Get));
+        assert!(req
+            .url
+            .starts_with("https://api.openweathermap.org/data/3.0/onecall?"));
+        assert!(req.body.is_empty());
+        assert!(!req.headers.iter().any(|(name, _)| name == "content-type"));
+    }
+
+    #[test]
+    fn post_request_carries_json_body() {
+        let fdw = scan(EndpointType::HourlyForecast);
+        let req = fdw.build_request(
+            http::Method::Post,
+            "http://localhost:8080/batch".to_string(),
+            Some(serde_json::json!({"locations": [{"lat": 52.52, "lon": 13.405}]})),
+        );
+
+        assert!(matches!(req.method, http::Method::Post));
+        assert_eq!(req.body, r#"{"locations":[{"lat":52.52,"lon":13.405}]}"#);
+        assert!(req
+            .headers
+            .iter()
+            .any(|(name, value)| name == "content-type" && value == "application/json"));
+    }
+
+    #[test]
+    fn fixture_current_weather() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.parse_current_weather(&fixture!("onecall")).unwrap();
+
+        let EndpointData::CurrentWeather {
+            timezone_name,
+            timezone_offset_seconds,
+            observation_time,
+            temperature_temp,
+            pressure_hpa,
+            uv_index,
+            visibility_m,
+            wind_gust_speed_m_s,
+            weather_condition,
+            weather_condition_code,
+            active_alerts_count,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(timezone_name.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(*timezone_offset_seconds, 7200);
+        assert_eq!(*observation_time, 1_729_936_800);
+        assert_eq!(*temperature_temp, 11.42);
+        assert_eq!(*pressure_hpa, Some(1021));
+        assert_eq!(*uv_index, Some(1.14));
+        assert_eq!(*visibility_m, Some(10000));
+        assert_eq!(*wind_gust_speed_m_s, Some(7.6));
+        assert_eq!(weather_condition, "Clouds");
+        assert_eq!(*weather_condition_code, Some(803));
+        assert_eq!(*active_alerts_count, Some(1));
+    }
+
+    #[test]
+    fn fixture_legacy_current_weather() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.city_id = Some(2950159);
+        fdw.parse_legacy_current_weather(&fixture!("weather"))
+            .unwrap();
+
+        let EndpointData::CurrentWeather {
+            city_id,
+            latitude,
+            longitude,
+            sunrise_time,
+            temperature_temp,
+            dew_point_temp,
+            cloud_cover_pct,
+            weather_description,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(*city_id, Some(2950159));
+        assert_eq!((*latitude, *longitude), (52.5244, 13.4105));
+        assert_eq!(*sunrise_time, Some(1_729_920_462));
+        assert_eq!(*temperature_temp, 11.42);
+        assert_eq!(*dew_point_temp, None);
+        assert_eq!(*cloud_cover_pct, 75);
+        assert_eq!(weather_description, "broken clouds");
+    }
+
+    #[test]
+    fn fixture_minutely_forecast_and_summary() {
+        let resp = fixture!("onecall");
+        let mut fdw = scan(EndpointType::MinutelyForecast);
+        fdw.parse_minutely_forecast(&resp).unwrap();
+        let EndpointData::MinutelyForecast {
+            forecast_time,
+            precipitation_mm,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(forecast_time.len(), 3);
+        assert_eq!(precipitation_mm, &[0.0, 0.21, 0.48]);
+
+        let mut fdw = scan(EndpointType::MinutelySummary);
+        fdw.parse_minutely_summary(&resp).unwrap();
+        let EndpointData::MinutelySummary {
+            total_precip_mm,
+            minutes_until_precip,
+            peak_precip_mm,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert!((total_precip_mm - 0.69).abs() < 1e-9);
+        assert_eq!(*minutes_until_precip, Some(1));
+        assert_eq!(*peak_precip_mm, 0.48);
+    }
+
+    #[test]
+    fn fixture_hourly_forecast() {
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        fdw.parse_hourly_forecast(&fixture!("onecall")).unwrap();
+
+        let EndpointData::HourlyForecast {
+            forecast_time,
+            temperature_temp,
+            precipitation_probability,
+            rain_volume_1h_mm,
+            precipitation_type,
+            weather_condition,
+            weather_condition_code,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(forecast_time, &[1_729_936_800, 1_729_940_400]);
+        assert_eq!(temperature_temp, &[11.42, 12.03]);
+        assert_eq!(precipitation_probability, &[0.2, 0.64]);
+        assert_eq!(rain_volume_1h_mm, &[None, Some(0.42)]);
+        assert_eq!(precipitation_type, &["none", "rain"]);
+        assert_eq!(weather_condition, &["Clouds", "Rain"]);
+        assert_eq!(weather_condition_code, &[Some(803), Some(500)]);
+    }
+
+    #[test]
+    fn fixture_daily_forecast() {
+        let mut fdw = scan(EndpointType::DailyForecast);
+        fdw.parse_daily_forecast(&fixture!("onecall")).unwrap();
+
+        let EndpointData::DailyForecast {
+            forecast_date,
+            temperature_min_temp,
+            temperature_max_temp,
+            moon_phase_fraction,
+            rain_volume_mm,
+            uv_index,
+            weather_icon_code,
+            day_summary_text,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(forecast_date, &[1_729_940_400, 1_730_026_800]);
+        assert_eq!(temperature_min_temp, &[7.85, 5.32]);
+        assert_eq!(temperature_max_temp, &[12.61, 11.76]);
+        assert_eq!(moon_phase_fraction, &[0.78, 0.81]);
+        assert_eq!(rain_volume_mm, &[Some(1.37), None]);
+        assert!(matches!(
+            fdw.get_cell_value("rain_volume_in").unwrap(),
+            Some(Cell::Numeric(inches)) if (inches - 1.37 / 25.4).abs() < 1e-12
+        ));
+        assert_eq!(uv_index, &[Some(1.42), Some(1.61)]);
+        assert_eq!(weather_icon_code, &["10d", "01d"]);
+        assert_eq!(
+            day_summary_text[1].as_deref(),
+            Some("There will be clear sky today")
+        );
+    }
+
+    #[test]
+    fn fixture_weather_alerts() {
+        let mut fdw = scan(EndpointType::WeatherAlerts);
+        fdw.parse_weather_alerts(&fixture!("onecall")).unwrap();
+
+        let EndpointData::WeatherAlerts { alerts, .. } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_sender_name, "Deutscher Wetterdienst");
+        assert_eq!(alerts[0].alert_event_type, "wind gusts");
+        assert_eq!(alerts[0].alert_start_time, 1_729_936_800);
+        assert_eq!(alerts[0].alert_end_time, 1_729_980_000);
+        assert!(matches!(
+            fdw.get_cell_value("alert_duration_seconds").unwrap(),
+            Some(Cell::I64(43_200))
+        ));
+        assert_eq!(alerts[0].alert_tags, vec!["Wind".to_string()]);
+    }
+
+    #[test]
+    fn fixture_onecall_summary() {
+        let mut fdw = scan(EndpointType::OneCallSummary);
+        fdw.parse_onecall_summary(&fixture!("onecall")).unwrap();
+
+        let EndpointData::OneCallSummary {
+            temperature_temp,
+            weather_condition,
+            next_hour_precip_mm,
+            today_min_temp,
+            today_max_temp,
+            active_alerts_count,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(*temperature_temp, 11.42);
+        assert_eq!(weather_condition, "Clouds");
+        assert!(next_hour_precip_mm.is_some_and(|mm| (mm - 0.69).abs() < 1e-9));
+        assert_eq!(*today_min_temp, Some(7.85));
+        assert_eq!(*today_max_temp, Some(12.61));
+        assert_eq!(*active_alerts_count, Some(1));
+    }
+
+    #[test]
+    fn fixture_historical_weather() {
+        let mut fdw = scan(EndpointType::HistoricalWeather);
+        fdw.parse_historical_weather(&fixture!("timemachine"))
+            .unwrap();
+
+        let EndpointData::HistoricalWeather { observations, .. } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        let HistoricalObservation {
+            observation_time,
+            temperature_temp,
+            pressure_hpa,
+            dew_point_temp,
+            wind_direction_deg,
+            weather_description,
+            ..
+        } = &observations[0];
+        assert_eq!(*observation_time, 1_730_073_600);
+        assert_eq!(*temperature_temp, 6.31);
+        assert_eq!(*pressure_hpa, Some(1029));
+        assert!(matches!(
+            fdw.get_cell_value("pressure_inhg").unwrap(),
+            Some(Cell::Numeric(inhg)) if (inhg - 1029.0 * 0.02953).abs() < 1e-9
+        ));
+        assert_eq!(*dew_point_temp, Some(4.62));
+        assert_eq!(*wind_direction_deg, 230);
+        assert_eq!(weather_description, "few clouds");
+    }
+
+    #[test]
+    fn fixture_daily_summary() {
+        let mut fdw = scan(EndpointType::DailySummary);
+        fdw.data = EndpointData::DailySummary {
+            summaries: Vec::new(),
+        };
+        fdw.parse_daily_summary(&fixture!("day_summary")).unwrap();
+
+        let EndpointData::DailySummary { summaries } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(summary.summary_date, "2024-10-15");
+        assert_eq!(summary.timezone_offset, "+01:00");
+        assert_eq!(summary.temperature_min_temp, 8.21);
+        assert_eq!(summary.temperature_max_temp, 15.7);
+        assert_eq!(summary.precipitation_total_mm, 1.2);
+        assert_eq!(summary.wind_max_speed_m_s, 6.7);
+        assert_eq!(summary.wind_max_direction_deg, 240.0);
+    }
+
+    #[test]
+    fn fixture_weather_overview() {
+        let mut fdw = scan(EndpointType::WeatherOverview);
+        fdw.parse_weather_overview(&fixture!("overview")).unwrap();
+
+        let EndpointData::WeatherOverview {
+            overview_date,
+            unit_system,
+            weather_overview,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(overview_date, "2024-10-26");
+        assert_eq!(unit_system, "metric");
+        assert!(weather_overview.starts_with("The current weather is overcast"));
+    }
+
+    #[test]
+    fn fixture_air_pollution() {
+        let mut fdw = scan(EndpointType::AirPollution);
+        fdw.parse_air_pollution(&fixture!("air_pollution")).unwrap();
+
+        let EndpointData::AirPollution {
+            observation_time,
+            aqi,
+            no2_ug_m3,
+            pm2_5_ug_m3,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(*observation_time, 1_729_936_800);
+        assert_eq!(*aqi, 2);
+        assert_eq!(*no2_ug_m3, Some(14.74));
+        assert_eq!(*pm2_5_ug_m3, Some(6.31));
+
+        let mut resp = fixture!("air_pollution");
+        resp["list"][0]["components"]
+            .as_object_mut()
+            .unwrap()
+            .remove("nh3");
+        fdw.parse_air_pollution(&resp).unwrap();
+        assert!(fdw.get_cell_value("nh3_ug_m3").unwrap().is_none());
+    }
+
+    #[test]
+    fn fixture_geocoding() {
+        let mut fdw = scan(EndpointType::Geocoding);
+        fdw.city_name = Some("Berlin".to_string());
+        fdw.parse_geocoding(&fixture!("geocoding")).unwrap();
+
+        let EndpointData::Geocoding { matches, .. } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].country_code, "DE");
+        assert_eq!(matches[1].state_name.as_deref(), Some("New Hampshire"));
+        assert_eq!(matches[1].longitude, -71.1850854);
+    }
+
+    #[test]
+    fn fixture_cities_in_bbox() {
+        let mut fdw = scan(EndpointType::CitiesInBbox);
+        fdw.bbox = Some(BoundingBox {
+            min_lat: 52.3,
+            min_lon: 13.0,
+            max_lat: 52.7,
+            max_lon: 13.8,
+            zoom: 10,
+        });
+        fdw.parse_cities_in_bbox(&fixture!("box_city")).unwrap();
+
+        let EndpointData::CitiesInBbox { cities, .. } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(cities.len(), 2);
+        assert_eq!(cities[1].city_name, "Potsdam");
+        assert_eq!(
+            (cities[1].latitude, cities[1].longitude),
+            (52.39886, 13.06566)
+        );
+        assert_eq!(cities[1].pressure_hpa, Some(1022));
+        assert_eq!(cities[1].weather_condition_code, Some(500));
+    }
+
+    #[test]
+    fn fixture_cities_group() {
+        let mut fdw = scan(EndpointType::CitiesGroup);
+        fdw.group_city_ids = vec![2950159, 2643743];
+        fdw.group_city_ids_text = "2950159,2643743".to_string();
+        fdw.parse_cities_group(&fixture!("group")).unwrap();
+
+        let EndpointData::CitiesGroup { city_ids, cities } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert_eq!(city_ids, "2950159,2643743");
+        assert_eq!(cities.len(), 2);
+        assert_eq!(cities[1].city_id, 2643743);
+        assert_eq!(cities[1].temperature_temp, 9.83);
+        assert_eq!(cities[1].humidity_pct, Some(87));
+    }
+
+    #[test]
+    fn parameter_quals_for_other_endpoints_are_ignored() {
+        let fields: Vec<String> = ["latitude", "observation_time", "summary_date"]
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+
+        assert_eq!(
+            EndpointType::CurrentWeather.ignored_parameter_quals(&fields),
+            vec!["observation_time", "summary_date"]
+        );
+        assert_eq!(
+            EndpointType::HistoricalWeather.ignored_parameter_quals(&fields),
+            vec!["summary_date"]
+        );
+        assert!(EndpointType::CurrentWeather
+            .ignored_parameter_quals(&fields[..1])
+            .is_empty());
+    }
+
+    #[test]
+    fn descending_scan_walks_rows_in_reverse() {
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        fdw.parse_hourly_forecast(&fixture!("onecall")).unwrap();
+        fdw.descending = true;
+
+        let mut times = Vec::new();
+        while fdw.current_row < fdw.data.row_count() {
+            let cell = fdw.get_cell_value("forecast_time").unwrap();
+            let Some(Cell::Timestamptz(t)) = cell else {
+                panic!("unexpected cell: {:?}", cell);
+            };
+            times.push(t / 1_000_000);
+            fdw.current_row += 1;
+        }
+        assert_eq!(times, vec![1_729_940_400, 1_729_936_800]);
+    }
+
+    #[test]
+    fn overview_temperature_mentions() {
+        let text = "Cloudy, highs around 24°C and lows near -3 °C overnight; wind below 5 m/s.";
+        assert_eq!(mentioned_temperature(text, &["high"]), Some(24.0));
+        assert_eq!(mentioned_temperature(text, &["low"]), Some(-3.0));
+        assert_eq!(
+            mentioned_temperature("Expect a high of 75 degrees.", &["high"]),
+            Some(75.0)
+        );
+        assert_eq!(mentioned_temperature("Lows in 2 days.", &["low"]), None);
+
+        let mut fdw = scan(EndpointType::WeatherOverview);
+        fdw.parse_weather_overview(&fixture!("overview")).unwrap();
+        assert!(fdw.get_cell_value("mentioned_high_temp").unwrap().is_none());
+
+        fdw.extract_overview_metrics = true;
+        fdw.parse_weather_overview(&fixture!("overview")).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("mentioned_high_temp").unwrap(),
+            Some(Cell::Numeric(t)) if t == 13.0
+        ));
+        assert!(fdw.get_cell_value("mentioned_low_temp").unwrap().is_none());
+    }
+
+    #[test]
+    fn dates_must_be_real_yyyy_mm_dd() {
+        assert_eq!(OpenWeatherFdw::parse_date("1970-01-02"), Some(1));
+        assert!(OpenWeatherFdw::parse_date("2024-02-29").is_some());
+        for bad in [
+            "2023-02-29",
+            "2024-04-31",
+            "2024-13-01",
+            "2024-1-15",
+            "24-01-15",
+            "2024-01-15x",
+        ] {
+            assert_eq!(OpenWeatherFdw::parse_date(bad), None, "{}", bad);
+        }
+        assert_eq!(
+            OpenWeatherFdw::validate_date("summary_date", "2024-02-30").unwrap_err(),
+            "summary_date must be YYYY-MM-DD, got '2024-02-30'"
+        );
+    }
+
+    #[test]
+    fn timezone_offsets_are_normalised() {
+        for (given, expected) in [
+            ("+02:00", "+02:00"),
+            ("+0200", "+02:00"),
+            ("-0530", "-05:30"),
+            ("+09", "+09:00"),
+        ] {
+            assert_eq!(
+                OpenWeatherFdw::normalize_timezone_offset(given).unwrap(),
+                expected
+            );
+        }
+        for bad in [
+            "02:00", "+2", "+02:0", "+15:00", "+02:60", "+02-00", "UTC", "+1€x",
+        ] {
+            assert!(
+                OpenWeatherFdw::normalize_timezone_offset(bad).is_err(),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn request_delay_counts_only_whole_elapsed_seconds() {
+        assert_eq!(OpenWeatherFdw::request_delay_ms(500, None, 100), 0);
+        assert_eq!(OpenWeatherFdw::request_delay_ms(500, Some(100), 100), 500);
+        assert_eq!(OpenWeatherFdw::request_delay_ms(500, Some(100), 101), 500);
+        assert_eq!(OpenWeatherFdw::request_delay_ms(1500, Some(100), 102), 500);
+        assert_eq!(OpenWeatherFdw::request_delay_ms(1500, Some(100), 105), 0);
+    }
+
+    #[test]
+    fn response_without_expected_keys_is_rejected() {
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        fdw.check_response_shape = true;
+        assert!(fdw
+            .check_response_shape(EndpointType::HourlyForecast, &fixture!("onecall"))
+            .is_ok());
+
+        let changed = serde_json::json!({ "latitude": 52.52, "forecast": [] });
+        let err = fdw
+            .check_response_shape(EndpointType::HourlyForecast, &changed)
+            .unwrap_err();
+        assert!(err.starts_with("unexpected response shape for hourly_forecast"));
+        assert!(err.contains("OpenWeather API may have changed"));
+
+        fdw.check_response_shape = false;
+        assert!(fdw
+            .check_response_shape(EndpointType::HourlyForecast, &changed)
+            .is_ok());
+    }
+
+    #[test]
+    fn city_name_resolves_to_first_geocoding_match() {
+        assert_eq!(
+            OpenWeatherFdw::first_geocoding_match(&fixture!("geocoding")),
+            Some((52.5170365, 13.3888599))
+        );
+        assert_eq!(
+            OpenWeatherFdw::first_geocoding_match(&serde_json::json!([])),
+            None
+        );
+
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.city_name = Some("Berlin".to_string());
+        fdw.parse_current_weather(&fixture!("onecall")).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("city_name").unwrap(),
+            Some(Cell::String(name)) if name == "Berlin"
+        ));
+    }
+
+    #[test]
+    fn extra_headers_parse_from_json_object() {
+        let headers =
+            OpenWeatherFdw::parse_extra_headers(r#"{"X-Proxy-Token": "abc", "accept": "*/*"}"#)
+                .unwrap();
+        assert_eq!(
+            headers,
+            vec![
+                ("x-proxy-token".to_string(), "abc".to_string()),
+                ("accept".to_string(), "*/*".to_string()),
+            ]
+        );
+
+        for bad in [
+            r#"["x"]"#,
+            r#"{"bad name": "x"}"#,
+            r#"{"x-n": 1}"#,
+            r#"{"x-n": "a\r\nb"}"#,
+            "x",
+        ] {
+            assert!(OpenWeatherFdw::parse_extra_headers(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn extra_params_are_appended_verbatim() {
+        assert!(OpenWeatherFdw::validate_extra_params("foo=bar&baz=1").is_ok());
+        for bad in [
+            "appid=x",
+            "foo=1&APPID=x",
+            "&foo=1",
+            "foo=a b",
+            "foo=1#x",
+            "?foo=1",
+        ] {
+            assert!(
+                OpenWeatherFdw::validate_extra_params(bad).is_err(),
+                "{}",
+                bad
+            );
+        }
+
+        let mut fdw = scan(EndpointType::AirPollution);
+        fdw.extra_params = Some("foo=bar&baz=1".to_string());
+        let req = fdw.create_request().unwrap();
+        assert!(req.url.ends_with("&foo=bar&baz=1"), "{}", req.url);
+    }
+
+    #[test]
+    fn uv_index_risk_bands() {
+        let bands: Vec<_> = [0.0, 2.4, 2.6, 5.0, 7.4, 8.0, 10.4, 11.0, 14.2]
+            .iter()
+            .map(|&uvi| OpenWeatherFdw::uv_index_risk(uvi))
+            .collect();
+        assert_eq!(
+            bands,
+            [
+                "Low",
+                "Low",
+                "Moderate",
+                "Moderate",
+                "High",
+                "Very High",
+                "Very High",
+                "Extreme",
+                "Extreme"
+            ]
+        );
+    }
+
+    #[test]
+    fn rain_outlook_finds_first_rainy_hour() {
+        let mut fdw = scan(EndpointType::RainOutlook);
+        fdw.rain_pop_threshold = 0.5;
+        fdw.rain_horizon_hours = 12;
+        fdw.parse_rain_outlook(&fixture!("onecall")).unwrap();
+        let EndpointData::RainOutlook {
+            will_rain,
+            rain_starts_at,
+            max_pop,
+            ..
+        } = &fdw.data
+        else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        assert!(*will_rain);
+        assert_eq!(*rain_starts_at, Some(1_729_940_400));
+        assert_eq!(*max_pop, 0.64);
+
+        fdw.rain_horizon_hours = 1;
+        fdw.parse_rain_outlook(&fixture!("onecall")).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("will_rain").unwrap(),
+            Some(Cell::Bool(false))
+        ));
+        assert!(fdw.get_cell_value("rain_starts_at").unwrap().is_none());
+    }
+
+    #[test]
+    fn http_status_errors_have_actionable_messages() {
+        let status_error =
+            |code| OpenWeatherFdw::status_error(code, EndpointType::DailySummary, false, None);
+
+        assert!(status_error(401).unwrap().starts_with(
+            "OpenWeather rejected the API key (401). Check the api_key server option"
+        ));
+        let forbidden = status_error(403).unwrap();
+        assert!(forbidden.contains("daily_summary (403)"));
+        assert!(forbidden.contains("/onecall/day_summary"));
+        assert!(!forbidden.contains("One Call by Call"));
+        assert!(
+            OpenWeatherFdw::status_error(403, EndpointType::HourlyForecast, true, None)
+                .unwrap()
+                .contains("One Call API 3.0 needs a separate 'One Call by Call' subscription")
+        );
+        assert!(status_error(404).unwrap().contains(
+            "endpoint not found (404) for daily_summary; check the api_url and api_version"
+        ));
+        assert!(status_error(429)
+            .unwrap()
+            .contains("rate limit exceeded (429)"));
+        assert!(status_error(500).is_none());
+
+        let city_not_found = "OpenWeather API error 404: city not found";
+        assert!(OpenWeatherFdw::status_error(
+            404,
+            EndpointType::CurrentWeather,
+            false,
+            Some(city_not_found)
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn forecast_unifies_hourly_and_daily_rows() {
+        let mut fdw = scan(EndpointType::Forecast);
+        fdw.parse_forecast(&fixture!("onecall")).unwrap();
+
+        let EndpointData::Forecast { rows, .. } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        let granularities: Vec<_> = rows.iter().map(|row| row.granularity).collect();
+        assert_eq!(granularities, ["hourly", "hourly", "daily", "daily"]);
+
+        let (hour, day) = (&rows[1], &rows[2]);
+        assert_eq!(hour.temperature_temp, 12.03);
+        assert_eq!(hour.rain_volume_mm, Some(0.42));
+        assert_eq!(hour.temperature_max_temp, None);
+        assert_eq!(hour.weather_condition_code, Some(500));
+        assert_eq!(day.temperature_min_temp, Some(7.85));
+        assert_eq!(day.temperature_max_temp, Some(12.61));
+        assert_eq!(day.rain_volume_mm, Some(1.37));
+        assert_eq!(day.visibility_m, None);
+    }
+
+    #[test]
+    fn wkt_point_parses_lon_lat() {
+        assert_eq!(
+            parse_wkt_point("POINT(13.405 52.52)"),
+            Some((13.405, 52.52))
+        );
+        assert_eq!(
+            parse_wkt_point(" SRID=4326;point ( -0.1276  51.5072 ) "),
+            Some((-0.1276, 51.5072))
+        );
+        assert_eq!(parse_wkt_point("POINT(13.405)"), None);
+        assert_eq!(parse_wkt_point("POINT(13.405 52.52 34)"), None);
+        assert_eq!(parse_wkt_point("LINESTRING(0 0, 1 1)"), None);
+        assert_eq!(parse_wkt_point("POINT(NaN 52.52)"), None);
+    }
+
+    #[test]
+    fn cell_type_names_describe_non_numeric_quals() {
+        assert_eq!(cell_type_name(&Cell::String("abc".to_string())), "a string");
+        assert_eq!(cell_type_name(&Cell::Bool(true)), "a boolean");
+        assert_eq!(cell_type_name(&Cell::Timestamptz(0)), "a timestamp");
+    }
+
+    #[test]
+    fn refresh_hints_follow_update_cadence() {
+        let refresh = |endpoint_type: EndpointType| endpoint_type.recommended_refresh_seconds();
+        assert_eq!(refresh(EndpointType::MinutelyForecast), Some(600));
+        assert_eq!(refresh(EndpointType::HourlyForecast), Some(3_600));
+        assert_eq!(refresh(EndpointType::DailyForecast), Some(86_400));
+        assert_eq!(refresh(EndpointType::HistoricalWeather), None);
+    }
+
+    #[test]
+    fn historical_observations_accumulate_per_timestamp() {
+        let mut fdw = scan(EndpointType::HistoricalWeather);
+        let mut later = fixture!("timemachine");
+        later["data"][0]["dt"] = serde_json::json!(1_730_077_200);
+        fdw.parse_historical_weather(&fixture!("timemachine"))
+            .unwrap();
+        fdw.parse_historical_weather(&later).unwrap();
+
+        assert_eq!(fdw.data.row_count(), 2);
+        fdw.current_row = 1;
+        assert!(matches!(
+            fdw.get_cell_value("dt").unwrap(),
+            Some(Cell::I64(1_730_077_200))
+        ));
+    }
+
+    #[test]
+    fn api_message_is_captured_from_successful_responses() {
+        let mut resp = fixture!("day_summary");
+        assert_eq!(OpenWeatherFdw::api_message(&resp), None);
+        resp["message"] = serde_json::json!(" partial data for this date ");
+
+        let mut fdw = scan(EndpointType::DailySummary);
+        fdw.parse_daily_summary(&resp).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("api_message").unwrap(),
+            Some(Cell::String(message)) if message == "partial data for this date"
+        ));
+        assert_eq!(
+            OpenWeatherFdw::api_message(&serde_json::json!({"message": ""})),
+            None
+        );
+    }
+
+    #[test]
+    fn daily_temperature_range_is_max_minus_min() {
+        let mut fdw = scan(EndpointType::DailyForecast);
+        fdw.parse_daily_forecast(&fixture!("onecall")).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("temperature_range_temp").unwrap(),
+            Some(Cell::Numeric(range)) if (range - (12.61 - 7.85)).abs() < 1e-9
+        ));
+    }
+
+    #[test]
+    fn as_of_timestamps_parse_as_utc() {
+        let parse = OpenWeatherFdw::parse_timestamp;
+        assert_eq!(parse("1730073600"), Some(1_730_073_600));
+        assert_eq!(parse("2024-10-28"), Some(1_730_073_600));
+        assert_eq!(parse("2024-10-28 00:00:00+00"), Some(1_730_073_600));
+        assert_eq!(parse("2024-10-28T02:00Z"), Some(1_730_080_800));
+        assert_eq!(parse("2024-10-28T02:00:00+02:00"), Some(1_730_073_600));
+        assert_eq!(parse("2024-10-28 24:00"), None);
+        assert_eq!(parse("yesterday"), None);
+        assert!(OpenWeatherFdw::validate_as_of("1970-01-01").is_err());
+    }
+
+    #[test]
+    fn has_active_alerts_is_a_boolean_flag() {
+        let mut fdw = scan(EndpointType::OneCallSummary);
+        fdw.parse_onecall_summary(&fixture!("onecall")).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("has_active_alerts").unwrap(),
+            Some(Cell::Bool(true))
+        ));
+
+        fdw.endpoint_type = Some(EndpointType::CurrentWeather);
+        fdw.parse_legacy_current_weather(&fixture!("weather"))
+            .unwrap();
+        assert!(fdw.get_cell_value("has_active_alerts").unwrap().is_none());
+    }
+
+    #[test]
+    fn order_alerts_puts_most_severe_first() {
+        let mut resp = fixture!("onecall");
+        let mut minor = resp["alerts"][0].clone();
+        minor["tags"] = serde_json::json!(["Minor"]);
+        let mut extreme = minor.clone();
+        extreme["tags"] = serde_json::json!(["Extreme heat"]);
+        extreme["start"] = serde_json::json!(1_729_990_000);
+        let untagged = serde_json::json!({"event": "Notice", "start": 1, "end": 2, "tags": []});
+        resp["alerts"] = serde_json::json!([minor, untagged, extreme]);
+
+        let mut fdw = scan(EndpointType::WeatherAlerts);
+        fdw.parse_weather_alerts(&resp).unwrap();
+        let EndpointData::WeatherAlerts { alerts, .. } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        let events: Vec<_> = alerts.iter().map(|a| a.alert_event_type.as_str()).collect();
+        assert_eq!(events[1], "Notice");
+
+        fdw.order_alerts = true;
+        fdw.parse_weather_alerts(&resp).unwrap();
+        let EndpointData::WeatherAlerts { alerts, .. } = &fdw.data else {
+            panic!("unexpected data: {:?}", fdw.data);
+        };
+        let severities: Vec<_> = alerts.iter().map(|a| a.alert_severity.as_deref()).collect();
+        assert_eq!(severities, [Some("Extreme"), Some("Minor"), None]);
+    }
+
+    #[test]
+    fn wind_speed_knots_follow_active_units() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        assert!((fdw.wind_speed_knots(10.0) - 19.438_444_924).abs() < 1e-6);
+        fdw.units = "imperial".to_string();
+        assert!((fdw.wind_speed_knots(10.0) - 8.689_762_419).abs() < 1e-6);
+    }
+
+    #[test]
+    fn location_tolerance_rejects_snapped_responses() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        let resp = serde_json::json!({"lat": 52.5244, "lon": 13.4105});
+        fdw.check_location_tolerance(EndpointType::CurrentWeather, &resp)
+            .unwrap();
+
+        fdw.location_tolerance_deg = Some(0.01);
+        fdw.check_location_tolerance(EndpointType::CurrentWeather, &resp)
+            .unwrap();
+        fdw.location_tolerance_deg = Some(0.001);
+        let err = fdw
+            .check_location_tolerance(EndpointType::CurrentWeather, &resp)
+            .unwrap_err();
+        assert!(err.contains("0.0055°"), "{}", err);
+
+        let legacy = serde_json::json!({"coord": {"lat": 52.52, "lon": 13.405}});
+        assert_eq!(
+            OpenWeatherFdw::response_location(&legacy),
+            Some((52.52, 13.405))
+        );
+    }
+
+    #[test]
+    fn moon_illumination_follows_phase() {
+        let pct = OpenWeatherFdw::moon_illumination_pct;
+        assert!(pct(0.0).abs() < 1e-9);
+        assert!(pct(1.0).abs() < 1e-9);
+        assert!((pct(0.5) - 100.0).abs() < 1e-9);
+        assert!((pct(0.25) - 50.0).abs() < 1e-9);
+        assert!((pct(0.75) - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn weather_icon_url_uses_icon_size() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.parse_current_weather(&fixture!("onecall")).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("weather_icon_url").unwrap(),
+            Some(Cell::String(url)) if url == "https://openweathermap.org/img/wn/04d@2x.png"
+        ));
+        fdw.icon_size = "4x".to_string();
+        assert_eq!(
+            fdw.weather_icon_url("10n"),
+            "https://openweathermap.org/img/wn/10n@4x.png"
+        );
+    }
+
+    #[test]
+    fn dry_run_builds_one_request_per_date() {
+        let mut fdw = scan(EndpointType::DailySummary);
+        fdw.base_url = "https://api.openweathermap.org/data/3.0".to_string();
+        fdw.api_key = "secret".to_string();
+        fdw.headers = vec![
+            ("user-agent".to_string(), "test".to_string()),
+            ("x-proxy-token".to_string(), "hunter2".to_string()),
+        ];
+        fdw.summary_dates = vec!["2024-01-01".to_string(), "2024-01-02".to_string()];
+        fdw.load_dry_run(EndpointType::DailySummary).unwrap();
+
+        assert_eq!(fdw.data.row_count(), 2);
+        fdw.current_row = 1;
+        let Some(Cell::String(url)) = fdw.get_cell_value("request_url").unwrap() else {
+            panic!("missing request_url");
+        };
+        assert!(url.contains("date=2024-01-02"), "{}", url);
+        assert!(
+            url.contains("appid=REDACTED") && !url.contains("secret"),
+            "{}",
+            url
+        );
+        assert!(matches!(
+            fdw.get_cell_value("request_headers").unwrap(),
+            Some(Cell::Json(headers))
+                if headers == r#"{"user-agent":"test","x-proxy-token":"REDACTED"}"#
+        ));
+        assert!(fdw
+            .get_cell_value("temperature_min_temp")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn weather_condition_is_language_stable() {
+        let localized = serde_json::json!({"id": 500, "main": "Rain", "description": "leichter Regen", "icon": "10d"});
+        let (condition, description, _) = OpenWeatherFdw::weather_fields(&localized);
+        assert_eq!(condition, "Rain");
+        assert_eq!(description, "leichter Regen");
+
+        let without_main = serde_json::json!({"id": 741, "description": "nebbia"});
+        assert_eq!(OpenWeatherFdw::weather_condition(&without_main), "Fog");
+        assert_eq!(
+            OpenWeatherFdw::weather_condition(&serde_json::json!({})),
+            "Unknown"
+        );
+    }
+
+    #[test]
+    fn precipitation_probability_pct_is_whole_percent() {
+        assert_eq!(OpenWeatherFdw::probability_pct(0.0), 0);
+        assert_eq!(OpenWeatherFdw::probability_pct(0.336), 34);
+        assert_eq!(OpenWeatherFdw::probability_pct(1.0), 100);
+
+        let mut fdw = scan(EndpointType::DailyForecast);
+        fdw.parse_daily_forecast(&fixture!("onecall")).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("precipitation_probability_pct").unwrap(),
+            Some(Cell::I64(64))
+        ));
+        assert!(matches!(
+            fdw.get_cell_value("precipitation_probability").unwrap(),
+            Some(Cell::Numeric(v)) if (v - 0.64).abs() < 1e-9
+        ));
+    }
+
+    #[test]
+    fn alert_event_normalized_folds_case_and_accents() {
+        assert_eq!(fold_to_ascii("Flood Warning"), "flood warning");
+        assert_eq!(
+            fold_to_ascii("  Überflutung   Straße "),
+            "uberflutung strasse"
+        );
+        assert_eq!(
+            fold_to_ascii("Alerte ORAGES Sévères"),
+            "alerte orages severes"
+        );
+        assert_eq!(fold_to_ascii("Гроза"), "гроза");
+
+        let mut resp = fixture!("onecall");
+        resp["alerts"][0]["event"] = serde_json::json!("Starkregen ÉCLAIRS");
+        let mut fdw = scan(EndpointType::WeatherAlerts);
+        fdw.parse_weather_alerts(&resp).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("alert_event_normalized").unwrap(),
+            Some(Cell::String(s)) if s == "starkregen eclairs"
+        ));
+        assert!(matches!(
+            fdw.get_cell_value("alert_event_type").unwrap(),
+            Some(Cell::String(s)) if s == "Starkregen ÉCLAIRS"
+        ));
+    }
+
+    #[test]
+    fn include_option_excludes_the_other_onecall_sections() {
+        let exclude = OpenWeatherFdw::onecall_exclude;
+        assert_eq!(
+            exclude(EndpointType::CurrentWeather, "current").unwrap(),
+            Some("minutely,hourly,daily,alerts".to_string())
+        );
+        assert_eq!(
+            exclude(EndpointType::Forecast, " Daily, hourly ").unwrap(),
+            Some("current,minutely,alerts".to_string())
+        );
+        assert_eq!(
+            exclude(
+                EndpointType::WeatherAlerts,
+                "current,minutely,hourly,daily,alerts"
+            )
+            .unwrap(),
+            None
+        );
+        assert!(exclude(EndpointType::CurrentWeather, "current,weekly")
+            .unwrap_err()
+            .contains("unknown section 'weekly'"));
+        assert!(exclude(EndpointType::HourlyForecast, "current,daily")
+            .unwrap_err()
+            .contains("must list 'hourly'"));
+        assert!(exclude(EndpointType::HistoricalWeather, "current").is_err());
+
+        let mut fdw = scan(EndpointType::DailyForecast);
+        fdw.onecall_exclude = exclude(EndpointType::DailyForecast, "daily").unwrap();
+        let req = fdw.create_request().unwrap();
+        assert!(req.url.ends_with("&exclude=current,minutely,hourly,alerts"));
+    }
+
+    #[test]
+    fn onecall_cache_key_covers_request_and_api_key() {
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        let full = fdw.onecall_cache_key().unwrap();
+
+        fdw.onecall_exclude = Some("current,minutely,daily,alerts".to_string());
+        let excluded = fdw.onecall_cache_key().unwrap();
+        assert_ne!(full, excluded);
+
+        fdw.onecall_exclude = None;
+        fdw.extra_params = Some("mode=json".to_string());
+        assert_ne!(full, fdw.onecall_cache_key().unwrap());
+
+        // A table-level API key must not be served the server key's response
+        fdw.extra_params = None;
+        fdw.api_key = "table-key".to_string();
+        assert_ne!(full, fdw.onecall_cache_key().unwrap());
+    }
+
+    #[test]
+    fn include_current_only_nulls_columns_from_excluded_sections() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.projected_columns = vec!["temperature_trend".to_string()];
+        fdw.onecall_exclude =
+            OpenWeatherFdw::onecall_exclude(EndpointType::CurrentWeather, "current").unwrap();
+        let mut resp = fixture!("onecall");
+        for section in ["minutely", "hourly", "daily", "alerts"] {
+            resp.as_object_mut().unwrap().remove(section);
+        }
+        fdw.parse_current_weather(&resp).unwrap();
+        for column in [
+            "temperature_trend",
+            "active_alerts_count",
+            "has_active_alerts",
+        ] {
+            assert!(fdw.get_cell_value(column).unwrap().is_none(), "{}", column);
+        }
+
+        let mut fdw = scan(EndpointType::OneCallSummary);
+        fdw.onecall_exclude =
+            OpenWeatherFdw::onecall_exclude(EndpointType::OneCallSummary, "current").unwrap();
+        fdw.parse_onecall_summary(&resp).unwrap();
+        for column in [
+            "active_alerts_count",
+            "has_active_alerts",
+            "next_hour_precip_mm",
+        ] {
+            assert!(fdw.get_cell_value(column).unwrap().is_none(), "{}", column);
+        }
+    }
+
+    #[test]
+    fn apparent_temp_delta_is_feels_like_minus_temp() {
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        fdw.parse_hourly_forecast(&fixture!("onecall")).unwrap();
+        let hourly = &fixture!("onecall")["hourly"][0];
+        let expected = hourly["feels_like"].as_f64().unwrap() - hourly["temp"].as_f64().unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("apparent_temp_delta").unwrap(),
+            Some(Cell::Numeric(v)) if (v - expected).abs() < 1e-9
+        ));
+
+        let mut fdw = scan(EndpointType::HistoricalWeather);
+        fdw.parse_historical_weather(&fixture!("timemachine"))
+            .unwrap();
+        let data = &fixture!("timemachine")["data"][0];
+        let expected = data["feels_like"].as_f64().unwrap() - data["temp"].as_f64().unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("apparent_temp_delta").unwrap(),
+            Some(Cell::Numeric(v)) if (v - expected).abs() < 1e-9
+        ));
+    }
+
+    #[test]
+    fn integer_quals_accept_only_integral_values() {
+        let to_i64 = OpenWeatherFdw::cell_to_i64;
+        assert_eq!(to_i64("zoom", &Cell::I32(7), 1..=i64::MAX), Ok(7));
+        assert_eq!(to_i64("zoom", &Cell::I16(3), 1..=i64::MAX), Ok(3));
+        assert_eq!(to_i64("limit", &Cell::Numeric(4.0), 1..=5), Ok(4));
+        assert_eq!(
+            to_i64("dt", &Cell::F64(1_730_073_600.0), i64::MIN..=i64::MAX),
+            Ok(1_730_073_600)
+        );
+
+        assert_eq!(
+            to_i64("limit", &Cell::Numeric(2.5), 1..=5),
+            Err("limit must be an integer, got 2.5".to_string())
+        );
+        assert_eq!(
+            to_i64("limit", &Cell::I64(9), 1..=5),
+            Err("limit must be an integer between 1 and 5, got 9".to_string())
+        );
+        assert_eq!(
+            to_i64("zoom", &Cell::I64(0), 1..=i64::MAX),
+            Err("zoom must be an integer of at least 1, got 0".to_string())
+        );
+        assert_eq!(
+            to_i64("city_id", &Cell::String("Berlin".to_string()), 1..=i64::MAX),
+            Err("city_id must be an integer, got a string".to_string())
+        );
+        assert!(to_i64("dt", &Cell::F64(1e19), i64::MIN..=i64::MAX).is_err());
+    }
+
+    #[test]
+    fn empty_timemachine_data_errors_unless_allowed() {
+        let mut resp = fixture!("timemachine");
+        resp["data"] = serde_json::json!([]);
+
+        let mut fdw = scan(EndpointType::HistoricalWeather);
+        let err = fdw.parse_historical_weather(&resp).unwrap_err();
+        assert!(err.starts_with("data array is empty"));
+
+        fdw.allow_empty = true;
+        fdw.data = EndpointData::HistoricalWeather {
+            latitude: fdw.latitude,
+            longitude: fdw.longitude,
+            observations: Vec::new(),
+        };
+        fdw.parse_historical_weather(&resp).unwrap();
+        assert_eq!(fdw.data.row_count(), 0);
+
+        // Other timestamps of an IN list still produce their rows
+        fdw.parse_historical_weather(&fixture!("timemachine"))
+            .unwrap();
+        assert_eq!(fdw.data.row_count(), 1);
+    }
+
+    #[test]
+    fn dry_run_with_as_of_reports_the_timemachine_request() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.base_url = "https://api.openweathermap.org/data/3.0".to_string();
+        fdw.as_of = Some(1_729_641_600);
+        fdw.load_dry_run(EndpointType::CurrentWeather).unwrap();
+
+        assert_eq!(fdw.endpoint_type, Some(EndpointType::CurrentWeather));
+        let Some(Cell::String(url)) = fdw.get_cell_value("request_url").unwrap() else {
+            panic!("missing request_url");
+        };
+        assert!(
+            url.contains("/onecall/timemachine?") && url.contains("dt=1729641600"),
+            "{}",
+            url
+        );
+    }
+
+    #[test]
+    fn onecall_cache_serves_a_later_fetch_until_the_ttl() {
+        let fdw = scan(EndpointType::HourlyForecast);
+        let key = fdw.onecall_cache_key().unwrap();
+        let resp = fixture!("onecall");
+        let fetched_at = 1_700_000_000;
+        // Round-trip through a string, as the entry is stored in stats metadata
+        let entry: JsonValue = serde_json::from_str(
+            &OpenWeatherFdw::onecall_cache_entry(&key, fetched_at, &resp).to_string(),
+        )
+        .unwrap();
+
+        let cached = OpenWeatherFdw::cached_onecall_response;
+        assert_eq!(cached(&entry, &key, fetched_at + 30), Some(resp));
+        assert_eq!(
+            cached(&entry, &key, fetched_at + ONECALL_CACHE_TTL_SECS),
+            None
+        );
+
+        let mut other = scan(EndpointType::HourlyForecast);
+        other.units = "imperial".to_string();
+        let other_key = other.onecall_cache_key().unwrap();
+        assert_eq!(cached(&entry, &other_key, fetched_at + 30), None);
+    }
+
+    #[test]
+    fn dry_run_echoes_city_name_and_parameters() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        fdw.city_name = Some("Berlin,DE".to_string());
+        fdw.lang = "de".to_string();
+        fdw.load_dry_run(EndpointType::CurrentWeather).unwrap();
+
+        assert!(matches!(
+            fdw.get_cell_value("city_name").unwrap(),
+            Some(Cell::String(name)) if name == "Berlin,DE"
+        ));
+        assert!(matches!(
+            fdw.get_cell_value("units").unwrap(),
+            Some(Cell::String(units)) if units == "metric"
+        ));
+        assert!(matches!(
+            fdw.get_cell_value("lang").unwrap(),
+            Some(Cell::String(lang)) if lang == "de"
+        ));
+
+        let mut fdw = scan(EndpointType::Geocoding);
+        fdw.city_name = Some("Paris".to_string());
+        fdw.geocoding_limit = 3;
+        fdw.load_dry_run(EndpointType::Geocoding).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("city_name").unwrap(),
+            Some(Cell::String(name)) if name == "Paris"
+        ));
+        assert!(matches!(
+            fdw.get_cell_value("limit").unwrap(),
+            Some(Cell::I64(3))
+        ));
+    }
+
+    #[test]
+    fn merge_descriptions_only_fills_weather_conditions_by_dt() {
+        let mut primary = fixture!("onecall");
+        primary["hourly"][1]["weather"][0]["description"] = JsonValue::from("");
+        primary["alerts"][0]["description"] = JsonValue::from("");
+
+        let mut fallback = fixture!("onecall");
+        for hour in fallback["hourly"].as_array_mut().unwrap() {
+            let label = format!("fallback {}", hour["dt"]);
+            hour["weather"][0]["description"] = JsonValue::from(label);
+        }
+        fallback["hourly"].as_array_mut().unwrap().reverse();
+        fallback["alerts"][0]["description"] = JsonValue::from("fallback alert");
+
+        OpenWeatherFdw::merge_descriptions(&mut primary, &fallback);
+        assert_eq!(
+            primary["hourly"][1]["weather"][0]["description"],
+            format!("fallback {}", primary["hourly"][1]["dt"])
+        );
+        assert_ne!(
+            primary["hourly"][0]["weather"][0]["description"],
+            format!("fallback {}", primary["hourly"][0]["dt"])
+        );
+        assert_eq!(primary["alerts"][0]["description"], "");
+    }
+
+    #[test]
+    fn temperature_trend_classifies_smoothed_change() {
+        let trend = OpenWeatherFdw::temperature_trend;
+        assert_eq!(trend(10.0, &[11.0, 12.0, 13.0]), Some("rising"));
+        assert_eq!(trend(10.0, &[9.0, 8.0, 7.0]), Some("falling"));
+        assert_eq!(trend(10.0, &[10.2, 9.9, 10.1]), Some("steady"));
+        assert_eq!(trend(10.0, &[]), None);
+    }
+
+    #[test]
+    fn current_weather_counts_active_alerts() {
+        let mut fdw = scan(EndpointType::CurrentWeather);
+        let mut resp = fixture!("onecall");
+        fdw.parse_current_weather(&resp).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("active_alerts_count").unwrap(),
+            Some(Cell::I64(1))
+        ));
+        assert!(matches!(
+            fdw.get_cell_value("has_active_alerts").unwrap(),
+            Some(Cell::Bool(true))
+        ));
+
+        // OpenWeather omits the 'alerts' array when nothing is active
+        resp.as_object_mut().unwrap().remove("alerts");
+        fdw.parse_current_weather(&resp).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("active_alerts_count").unwrap(),
+            Some(Cell::I64(0))
+        ));
+        assert!(matches!(
+            fdw.get_cell_value("has_active_alerts").unwrap(),
+            Some(Cell::Bool(false))
+        ));
+    }
+
+    #[test]
+    fn explode_weather_emits_one_row_per_condition() {
+        // The fixture's second hour reports both Rain and Mist
+        let mut fdw = scan(EndpointType::HourlyForecast);
+        fdw.explode_weather = true;
+        fdw.parse_hourly_forecast(&fixture!("onecall")).unwrap();
+        assert_eq!(fdw.data.row_count(), 3);
+
+        let mut conditions = Vec::new();
+        for row in 0..3 {
+            fdw.current_row = row;
+            let Some(Cell::String(condition)) = fdw.get_cell_value("weather_condition").unwrap()
+            else {
+                panic!("missing weather_condition");
+            };
+            let Some(Cell::Timestamptz(time)) = fdw.get_cell_value("forecast_time").unwrap() else {
+                panic!("missing forecast_time");
+            };
+            conditions.push((time, condition));
+        }
+        assert_eq!(conditions[0].1, "Clouds");
+        assert_eq!(conditions[1].1, "Rain");
+        assert_eq!(conditions[2].1, "Mist");
+        assert_eq!(conditions[1].0, conditions[2].0);
+        assert_ne!(conditions[0].0, conditions[1].0);
+    }
+
+    #[test]
+    fn alert_time_quals_filter_alerts_by_range() {
+        let mut resp = fixture!("onecall");
+        let template = resp["alerts"][0].clone();
+        let alerts: Vec<JsonValue> = [(1_000, 2_000), (3_000, 4_000), (5_000, 6_000)]
+            .iter()
+            .map(|&(start, end)| {
+                let mut alert = template.clone();
+                alert["start"] = JsonValue::from(start);
+                alert["end"] = JsonValue::from(end);
+                alert
+            })
+            .collect();
+        resp["alerts"] = JsonValue::Array(alerts);
+        let us = |secs: i64| secs * 1_000_000;
+        let starts = |fdw: &OpenWeatherFdw| -> Vec<i64> {
+            let EndpointData::WeatherAlerts { alerts, .. } = &fdw.data else {
+                panic!("unexpected data: {:?}", fdw.data);
+            };
+            alerts.iter().map(|a| a.alert_start_time).collect()
+        };
+
+        // Inclusive lower bound keeps an alert starting on it, exclusive upper drops one
+        let mut fdw = scan(EndpointType::WeatherAlerts);
+        fdw.alert_start_range = Some((Bound::Included(us(3_000)), Bound::Excluded(us(5_000))));
+        fdw.parse_weather_alerts(&resp).unwrap();
+        assert_eq!(starts(&fdw), vec![3_000]);
+
+        // Active at T = 3_500: alert_start_time <= T AND alert_end_time >= T
+        fdw.alert_start_range = Some((Bound::Unbounded, Bound::Included(us(3_500))));
+        fdw.alert_end_range = Some((Bound::Included(us(3_500)), Bound::Unbounded));
+        fdw.parse_weather_alerts(&resp).unwrap();
+        assert_eq!(starts(&fdw), vec![3_000]);
+
+        // Exclusive lower bound drops an alert ending exactly on it
+        fdw.alert_start_range = None;
+        fdw.alert_end_range = Some((Bound::Excluded(us(4_000)), Bound::Unbounded));
+        fdw.parse_weather_alerts(&resp).unwrap();
+        assert_eq!(starts(&fdw), vec![5_000]);
+
+        fdw.alert_end_range = None;
+        fdw.parse_weather_alerts(&resp).unwrap();
+        assert_eq!(starts(&fdw), vec![1_000, 3_000, 5_000]);
+    }
+
+    #[test]
+    fn qual_extraction_builds_bounds_from_operators() {
+        // Without quals nothing is extracted (and no host call is made)
+        let no_quals = [];
+        assert!(OpenWeatherFdw::find_qual(&no_quals, "summary_date", &["="]).is_none());
+        assert!(OpenWeatherFdw::extract_qual_range(&no_quals, "summary_date").is_none());
+        assert!(matches!(
+            OpenWeatherFdw::extract_qual_i64(&no_quals, "zoom", 1..=i64::MAX),
+            Ok(None)
+        ));
+
+        let qual = |op: &str, n: i64| Some((op.to_string(), Cell::I64(n)));
+        let bounds = OpenWeatherFdw::qual_bounds;
+        assert!(matches!(
+            bounds(None, qual(">=", 1), qual("<", 5)),
+            Some((Bound::Included(Cell::I64(1)), Bound::Excluded(Cell::I64(5))))
+        ));
+        assert!(matches!(
+            bounds(None, qual(">", 1), None),
+            Some((Bound::Excluded(Cell::I64(1)), Bound::Unbounded))
+        ));
+        assert!(matches!(
+            bounds(None, None, qual("<=", 5)),
+            Some((Bound::Unbounded, Bound::Included(Cell::I64(5))))
+        ));
+        // '=' pins both ends, whatever else is present
+        assert!(matches!(
+            bounds(Some(Cell::I64(3)), qual(">", 1), None),
+            Some((Bound::Included(Cell::I64(3)), Bound::Included(Cell::I64(3))))
+        ));
+        assert!(bounds(None, None, None).is_none());
+    }
+
+    #[test]
+    fn alert_description_lang_is_null_unless_declared() {
+        let mut resp = fixture!("onecall");
+        let mut fdw = scan(EndpointType::WeatherAlerts);
+        fdw.parse_weather_alerts(&resp).unwrap();
+        assert!(fdw
+            .get_cell_value("alert_description_lang")
+            .unwrap()
+            .is_none());
+
+        resp["alerts"][0]["language"] = JsonValue::from("de");
+        fdw.parse_weather_alerts(&resp).unwrap();
+        assert!(matches!(
+            fdw.get_cell_value("alert_description_lang").unwrap(),
+            Some(Cell::String(lang)) if lang == "de"
+        ));
+    }
+
+    #[test]
+    fn alert_severity_matches_whole_words() {
+        let severity = |tags: &[&str]| {
+            let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+            AlertRow::severity_from_tags(&tags)
+        };
+        assert_eq!(
+            severity(&["Wind", "severe-weather"]),
+            Some("Severe".to_string())
+        );
+        assert_eq!(
+            severity(&["MODERATE flooding"]),
+            Some("Moderate".to_string())
+        );
+        assert_eq!(severity(&["Minority report", "Nonsevere"]), None);
+        assert_eq!(severity(&[]), None);
+    }
+}